@@ -1,21 +1,51 @@
 
 
+import java.awt.image.BufferedImage;
+import java.io.BufferedWriter;
 import java.io.IOException;
+import java.io.InputStream;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.DirectoryStream;
 import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
 import java.nio.file.Paths;
+import java.nio.file.StandardOpenOption;
+import java.time.Clock;
+import java.time.Duration;
+import java.time.Instant;
 import java.time.LocalDate;
 import java.time.LocalTime;
+import java.time.ZonedDateTime;
 import java.time.format.DateTimeFormatter;
+import java.time.format.DateTimeParseException;
 import java.time.temporal.ChronoUnit;
+import java.util.ArrayDeque;
 import java.util.Arrays;
 import java.util.ArrayList;
+import java.util.Collections;
+import java.util.Collection;
 import java.util.List;
+import java.util.Map;
 import java.util.Set;
+import java.util.EnumMap;
+import java.util.HashMap;
 import java.util.HashSet;
+import java.util.Iterator;
+import java.util.LinkedHashMap;
 import java.util.Random;
-import java.util.Scanner;
 import java.util.TreeMap;
+import java.util.TreeSet;
 import java.util.NavigableMap;
+import java.util.concurrent.ConcurrentHashMap;
+import java.util.concurrent.ScheduledExecutorService;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipFile;
+import javax.imageio.ImageIO;
+import java.util.concurrent.ScheduledFuture;
+import java.util.concurrent.TimeUnit;
+import java.util.function.Consumer;
+import java.util.Objects;
 
 
 
@@ -30,7 +60,337 @@ import java.util.NavigableMap;
  */
 public class Spacerace {
     public String[] artLines;
+    private String[] originalArt;
+    // Star layout of the current frame, the pristine art unless star lifetimes are on
+    private String[] skyArt;
     private final NavigableMap<Integer, String> degreeArtMap = new TreeMap<>();
+    private Set<Integer> rowsToRemove = new HashSet<>();
+
+    // Printed by --version along with the commit and build time
+    public static final String VERSION = "0.1.0";
+
+    // Bucket crossing highlight, the art is inverted for a few frames when the file changes
+    private static final int HIGHLIGHT_FRAMES = 3;
+    private static final int FULL_ORBIT = 360;
+    private static final double UNIX_EPOCH_JULIAN_DATE = 2440587.5;
+    private static final double EARTH_ORBITAL_PERIOD_DAYS = 365.25;
+    // Reference date: January 1st, 2000, where Earth is considered to be at 0 degrees
+    private static final LocalDate REFERENCE_DATE = LocalDate.of(2000, 1, 1);
+    private static final long MAX_EPOCH_YEARS = 10_000;
+    private static final long FRAME_MILLIS = 1000;
+    // --interval sets the time between frames, --max-fps puts a floor under it
+    private static final int DEFAULT_MAX_FPS = 30;
+    private long frameMillis = FRAME_MILLIS;
+    private long minFrameMillis = 1000 / DEFAULT_MAX_FPS;
+    private static final String CLEAR_SCREEN = "\033[H\033[2J";
+    private static final DateTimeFormatter MEDIUM_CLOCKED_IN = DateTimeFormatter.ofPattern("HH:mm");
+    private String currentArtPath;
+    private int highlightFrames = 0;
+    private boolean borderless = false;
+    private boolean flipHorizontal = false;
+    private boolean flipVertical = false;
+    private boolean calendarYear = false;
+    private boolean bellOnChange = false;
+    private boolean scanMode = false;
+    private boolean doctorMode = false;
+    // --schedule HOURS lists the art changes coming up and exits
+    private long scheduleHours = 0;
+    private boolean onceMode = false;
+    private boolean showVersion = false;
+    private boolean showConfig = false;
+    private static final long FADE_IN_MILLIS = 1000;
+    private int fadeInSteps = 5;
+    private boolean showOpposite = false;
+    private double blendMargin = 0;
+    private double currentBlend = 0;
+    private long cycleDwellFrames = 0;
+    private int cycleSeconds = 0;
+    // --demo twinkles a fixed generated starfield and leaves the orbit out of it
+    private static final long DEMO_SEED = 42;
+    private boolean demoMode = false;
+    private String placeholder = "⟨ no scene for {angle}° ⟩";
+    private Double pinnedAngle = parseAngle(System.getenv("SPACERACE_ANGLE"));
+    // --random picks a bucket by weight each dwell period instead of stepping in order
+    private boolean randomScenes = false;
+    private final Map<Integer, Integer> bucketWeights = new HashMap<>();
+    private long randomPeriod = -1;
+    private int randomBucket;
+    private List<Integer> playableBuckets;
+    // Above the 30 degree stretches of the built in map, so a plain run starts without warnings
+    private int maxGapDegrees = 45;
+    // --background IMAGE and the --ramp it is drawn with, dark to light
+    private String backgroundPath;
+    private BufferedImage backgroundImage;
+    private String backgroundRamp = " .:-~oO%$@";
+    private static final String RESERVED_RAMP_CHARS = "*+┼&=#";
+    private long frameCount = 0;
+    private LocalDate lastSeasonEvent;
+    private double angleSmoothing = 1;
+    private double smoothedAngle = Double.NaN;
+    // Status bar under the art, segments in the order given and placed left, center or right
+    public enum StatusSegment { ANGLE, DATE, TIME, JULIAN, DAY, FPS }
+    private final Map<StatusSegment, Alignment> statusSegments = new LinkedHashMap<>();
+    private final ArrayDeque<Long> frameDurations = new ArrayDeque<>();
+    private long lastFrameNanos = 0;
+    private int fpsWindow = 10;
+
+    // Star tint, warmer near perihelion (early January) and cooler near aphelion
+    private static final double PERIHELION_ANGLE = 3.0;
+    private static final int[] WARM_STAR = {255, 214, 170};
+    private static final int[] COOL_STAR = {170, 200, 255};
+    private double currentPosition;
+    private double orbitalPeriodDays = EARTH_ORBITAL_PERIOD_DAYS;
+    // --body NAME picks an orbit out of the --bodies file, it replaces --period
+    private String bodiesPath = "bodies.txt";
+    // --constellations FILE, the day's constellation twinkles less, pulsing up to CONSTELLATION_BOOST times as bright
+    private static final double CONSTELLATION_BOOST = 3;
+    private static final int CONSTELLATION_PULSE_FRAMES = 20;
+    private String constellationsPath;
+    private double gradientTop = 1;
+    private double gradientBottom = 1;
+    private List<Constellation> constellations;
+    private String bodyName;
+    private String gridBodies;
+    private Body body;
+    private String viewpointName;
+    private Body viewpoint;
+
+    // Okabe-Ito colors, distinguishable with the common forms of color blindness
+    private static final int[] OKABE_ITO_ORANGE = {230, 159, 0};
+    private static final int[] OKABE_ITO_SKY_BLUE = {86, 180, 233};
+    private static final int[] OKABE_ITO_BLUISH_GREEN = {0, 158, 115};
+    private static final int[] OKABE_ITO_YELLOW = {240, 228, 66};
+    private static final int[] OKABE_ITO_BLUE = {0, 114, 178};
+    private static final int[] OKABE_ITO_VERMILLION = {213, 94, 0};
+    private static final int[] OKABE_ITO_REDDISH_PURPLE = {204, 121, 167};
+
+    // Star color by brightness for the RAMP palette, dim stars cool and dark, bright ones a warm white
+    private static final int[][] STAR_RAMP = {{48, 64, 128}, {140, 170, 255}, {255, 240, 200}};
+
+    /***************************************************************
+     * Color themes. PLAIN prints the art untouched, SEASONAL tints  *
+     * the stars by orbital angle and COLORBLIND uses Okabe-Ito.     *
+     * RAMP colors each star by how bright its glyph is right now.   *
+     * A null color leaves that part of the art uncolored.           *
+     ***************************************************************/
+
+    public enum Palette {
+        PLAIN, SEASONAL, COLORBLIND, RAMP;
+
+        public int[] starColor(char glyph, double angle) {
+            switch (this) {
+                case SEASONAL:
+                    return starTint(angle);
+                case RAMP:
+                    return rampColor(glyphBrightness(glyph));
+                case COLORBLIND:
+                    switch (glyph) {
+                        case '*': return OKABE_ITO_YELLOW;
+                        case '+': return OKABE_ITO_SKY_BLUE;
+                        case '.': return OKABE_ITO_BLUE;
+                        case '┼': return OKABE_ITO_ORANGE;
+                        default: return OKABE_ITO_VERMILLION;
+                    }
+                default:
+                    return null;
+            }
+        }
+
+        public int[] borderColor() {
+            return this == COLORBLIND ? OKABE_ITO_REDDISH_PURPLE : null;
+        }
+
+        public int[] textColor() {
+            return this == COLORBLIND ? OKABE_ITO_BLUISH_GREEN : null;
+        }
+
+        // Sky background for --gradient as {top, bottom}, dark overhead and lighter at the horizon
+        public int[][] gradient() {
+            if (this == COLORBLIND) {
+                return new int[][]{{0, 0, 0}, {0, 46, 71}};
+            }
+            return new int[][]{{0, 0, 8}, {24, 28, 56}};
+        }
+    }
+
+    private Palette palette = Palette.PLAIN;
+    // Themes the c key steps through, --themes narrows or reorders them
+    private List<Palette> themes = new ArrayList<>(List.of(Palette.values()));
+    // One line shown over the frame for a few frames after a key changes a setting
+    private String banner;
+    // --color-fade FRAMES, the colors being faded away from and how many frames are left
+    private static final int[] TERMINAL_DEFAULT_COLOR = {192, 192, 192};
+    private int colorFadeFrames = 3;
+    private Palette fromPalette = Palette.PLAIN;
+    private double fromPosition;
+    private int transitionFrames = 0;
+    private int bannerFrames = 0;
+    private boolean skyGradient = false;
+    // NO_COLOR (https://no-color.org) or --no-color turn every color and style escape off
+    private boolean colorEnabled = noColorUnset(System.getenv("NO_COLOR"));
+
+    // What the terminal can show, palettes are written in RGB and downgraded to fit
+    public enum ColorMode { TRUECOLOR, ANSI256, ANSI16 }
+    private ColorMode colorMode = detectColorMode(System.getenv("COLORTERM"), System.getenv("TERM"));
+    private static final int[][] ANSI16_COLORS = {
+            {0, 0, 0}, {205, 0, 0}, {0, 205, 0}, {205, 205, 0}, {0, 0, 238}, {205, 0, 205}, {0, 205, 205}, {229, 229, 229},
+            {127, 127, 127}, {255, 0, 0}, {0, 255, 0}, {255, 255, 0}, {92, 92, 255}, {255, 0, 255}, {0, 255, 255}, {255, 255, 255}
+    };
+    // --ansi-art lifts color escapes out of the art files, one RGB per cell or null
+    private boolean ansiArt = false;
+    // Art clean-up on load, --tab-width expands tabs and --trim drops trailing whitespace
+    private int tabWidth = 8;
+    private boolean trimTrailing = false;
+    // Art read ahead of a bucket crossing, taken out again by asciiArt
+    private static final double PREFETCH_MARGIN = 2.0;
+    private final Map<String, List<String>> artCache = new ConcurrentHashMap<>();
+    private volatile String prefetchPath;
+    private int[][][] artColors;
+    // Cells stamped by OVER_WRITE this frame as {row, start, length}
+    private final List<int[]> textSpans = new ArrayList<>();
+
+    // ISS ticker, a single blip crossing one row of the starfield
+    private boolean showIss = false;
+    private int issRow = 5;
+    private int issSpeed = 8;
+    private int issColumn = 0;
+    private boolean showSun = false;
+    private static final int TERMINATOR_COLUMNS = 12;
+    private static final int TERMINATOR_ROWS = 5;
+    private static final double AXIAL_TILT = 23.44;
+    // Angle of the March equinox in this orbit, 79 days after January 1st
+    private static final double VERNAL_EQUINOX_ANGLE = 78;
+    private boolean showTerminator = false;
+    // --orbit-dial, rows of the ring either side of the sun
+    private static final int DIAL_RADIUS = 3;
+    private boolean showOrbitDial = false;
+
+    // Hidden d key, a logo drifting around the art and bouncing off its edges
+    private static final String LOGO = "SPACE";
+    private static final int[] LOGO_CORNER_COLOR = {255, 0, 255};
+    private boolean showLogo = false;
+    private int logoRow = 0;
+    private int logoColumn = 0;
+    private int logoRowVelocity = 1;
+    private int logoColumnVelocity = 1;
+    private int logoFlashFrames = 0;
+
+    // Twinkle tables, each fade character is picked in proportion to its weight
+    private char[] starFadeChars = {'*', '+', '.', ' '};
+    private char[] plusFadeChars = {'┼', '├', '─', ' '};
+    // The glyph of the second kind of star in the pristine art, + under --ascii-only
+    private boolean asciiOnly = false;
+    private char plusStar = '┼';
+    private int[] starFadeWeights = {1, 1, 1, 1};
+    private int[] plusFadeWeights = {1, 1, 1, 1};
+
+    // Session recording, every frame goes out as "@<epoch millis> <line count>" plus its lines
+    private BufferedWriter recorder;
+    private String recordPath;
+    private String replayPath;
+    private static final int CSV_FLUSH_FRAMES = 10;
+    // Last frame on screen for --double-buffer, null forces a full redraw
+    private boolean doubleBuffer = false;
+    private List<String> previousFrame;
+    // --skip-unchanged, the last frame written out
+    private boolean skipUnchanged = false;
+    private String lastDrawn;
+    private BufferedWriter csvWriter;
+    private String csvPath;
+    private BufferedWriter eventLog;
+    private String eventLogPath;
+
+    // Everything that asks for the time asks this clock, swap in Clock.fixed to hold it still
+    private Clock clock = Clock.systemDefaultZone();
+
+    // --idle SECONDS, how long without a key before the twinkle slows down
+    private static final int IDLE_SLOWDOWN = 4;
+    private Duration idleAfter;
+    private volatile Instant lastInput = clock.instant();
+
+    private final Map<String, Action> keyBindings = defaultKeyBindings();
+    private String keysPath;
+    private volatile boolean paused = false;
+    private volatile boolean bucketAngle = false;
+
+    // Time machine, PageUp/PageDown move a virtual date this many days away from today
+    private long dayOffset = 0;
+    private String savedTerminalState;
+
+    // Rectangles of {top, left, bottom, right}, inclusive, where nothing twinkles
+    private final List<int[]> staticRegions = new ArrayList<>();
+
+    // Star lifetimes, mortal stars burn out after up to starLifetime frames and new ones are born
+    private static final double MORTAL_FRACTION = 0.3;
+    private int starLifetime = 0;
+    private final Map<Integer, Integer> mortalStars = new HashMap<>();
+    // --trail FRAMES, age of each cell's running trail keyed like mortalStars
+    private int trailLength = 0;
+    // --phase-period FRAMES, a fixed phase per cell rolled when the art loads
+    private int phasePeriod = 0;
+    private double[][] starPhases;
+    // --shimmer, each star's own angular frequency in radians a second
+    private static final double SHIMMER_MIN_HZ = 0.05;
+    private static final double SHIMMER_MAX_HZ = 0.5;
+    private boolean shimmer = false;
+    // Wall clock time the shimmer started, so it keeps its speed whatever the frame rate
+    private long shimmerStartMillis = -1;
+    private static final double WIND_WAVE = 0.3;
+    private double windX = 0;
+    private double windY = 0;
+    private double[][] starFrequencies;
+    private final Map<Integer, Integer> trails = new HashMap<>();
+    private final Set<Integer> deadStars = new HashSet<>();
+    private String[] lifetimeArt;
+    private int initialStarCount;
+
+    // Canvas override, 0 means print the art at its own size
+    private int canvasWidth = 0;
+    private int canvasHeight = 0;
+    private static final int[] DEFAULT_TERMINAL_SIZE = {80, 24};
+
+    // Odds a star twinkles in a frame, --moon-pulse steadies the stars on dark moonless nights
+    private static final double TWINKLE_CHANCE = 0.4;
+    private static final double NEW_MOON_JULIAN_DATE = 2451550.1;
+    private static final double SYNODIC_MONTH_DAYS = 29.530588853;
+    private boolean moonPulse = false;
+    private double twinkleChance = TWINKLE_CHANCE;
+    private static final double MIN_BRIGHTNESS = 0.5;
+    private static final double MAX_BRIGHTNESS = 2.0;
+    private static final double BRIGHTNESS_STEP = 0.1;
+    private double brightness = 1.0;
+    // --layout side puts an info panel this many columns right of the art
+    private static final int PANEL_GAP = 2;
+    private boolean sidePanel = false;
+
+    private static final int EXIT_OK = 0;
+    private static final int EXIT_FAILURE = 1;
+    private static final int EXIT_USAGE = 2;
+    private static final int EXIT_NO_ART = 3;
+    private static final int EXIT_IO = 4;
+    // --padding TOP[,BOTTOM] blank rows kept between the art and the frame edges
+    private int paddingTop = 0;
+    private int paddingBottom = 0;
+
+    // Where the art sits on a wider canvas, and which part is kept when the canvas is narrower
+    public enum Alignment { LEFT, CENTER, RIGHT }
+    private Alignment alignment = Alignment.CENTER;
+
+    // Marquee, lines wider than the canvas scroll by this many columns per frame
+    private int marqueeSpeed = 0;
+    private int marqueeOffset = 0;
+
+    // Map entries and special days are file names inside the art root
+    private static final String ART_ROOT = "asciiArt";
+    private static final String WINTER_SOLSTICE_PATH = "solstice.txt";
+    private static final String VERNAL_EQUINOX_PATH = "vernaleq.txt";
+    private static final String SUMMER_SOLSTICE_PATH = "summersolstice.txt";
+    private static final String AUTUMN_EQUINOX_PATH = "autumneq.txt";
+    private String artRoot = ART_ROOT;
+    private long maxArtBytes = 1024 * 1024;
+    private ZipFile artArchive;
+    private String artArchivePath;
+    private Random random = new Random();
 
 
     /*****************************************************************************************************
@@ -40,205 +400,3529 @@ public class Spacerace {
      * ***************************************************************************************************/
 
     public static double calculateEarthPosition(LocalDate date) {
-        // Reference date: January 1st, 2000, where Earth is considered to be at 0 degrees
-        LocalDate referenceDate = LocalDate.of(2000, 1, 1);
-        long daysBetween = ChronoUnit.DAYS.between(referenceDate, date);
+        return calculateEarthPosition(date, EARTH_ORBITAL_PERIOD_DAYS);
+    }
+
+    // Where the Earth is on the clock's date, a fixed or offset Clock pins it down for testing
+    public static double calculateEarthPosition(Clock clock) {
+        return calculateEarthPosition(LocalDate.now(clock));
+    }
+
+    /**************************************************************
+     * Julian Date, days since noon UTC on January 1st 4713 BC.     *
+     * J2000 (2000-01-01 12:00 UTC) comes out as 2451545.0.         *
+     **************************************************************/
+
+    public static double julianDate(ZonedDateTime dateTime) {
+        return dateTime.toInstant().toEpochMilli() / 86_400_000.0 + UNIX_EPOCH_JULIAN_DATE;
+    }
+
+    /*************************************************************
+     * Moon phase as a fraction of the synodic month, 0 at new     *
+     * moon and 0.5 at full, counted from the new moon of          *
+     * JD 2451550.1 (2000-01-06).                                  *
+     *************************************************************/
+
+    public static double moonPhase(ZonedDateTime dateTime) {
+        double months = (julianDate(dateTime) - NEW_MOON_JULIAN_DATE) / SYNODIC_MONTH_DAYS;
+        return ((months % 1) + 1) % 1;
+    }
+
+    // 1 on a new moon night when the sky is darkest, 0 under a full moon, smooth in between
+    public static double moonBrightness(ZonedDateTime dateTime) {
+        return 0.5 + 0.5 * Math.cos(2 * Math.PI * moonPhase(dateTime));
+    }
+
+    public static int dayOfYear(LocalDate date) {
+        return date.getDayOfYear();
+    }
+
+    /****************************************************************
+     * A body from the --bodies file, one per line as                *
+     *   name period-days eccentricity epoch                         *
+     * with the epoch being a date the body sits at 0 degrees.       *
+     * Blank lines and lines starting with # are skipped.            *
+     ****************************************************************/
+
+    public static final class Body {
+        public final String name;
+        public final double periodDays;
+        public final double eccentricity;
+        public final LocalDate epoch;
+
+        public Body(String name, double periodDays, double eccentricity, LocalDate epoch) {
+            this.name = name;
+            this.periodDays = periodDays;
+            this.eccentricity = eccentricity;
+            this.epoch = epoch;
+        }
+
+        public static List<Body> fromConfig(List<String> lines) {
+            List<Body> bodies = new ArrayList<>();
+            for (String line : lines) {
+                String trimmed = line.trim();
+                if (trimmed.isEmpty() || trimmed.startsWith("#")) {
+                    continue;
+                }
+                String[] fields = trimmed.split("\\s+");
+                if (fields.length != 4) {
+                    throw new IllegalArgumentException("Expected name period eccentricity epoch: " + line);
+                }
+                double period = Double.parseDouble(fields[1]);
+                double eccentricity = Double.parseDouble(fields[2]);
+                if (period <= 0 || eccentricity < 0 || eccentricity >= 1) {
+                    throw new IllegalArgumentException("Period must be positive and eccentricity in [0, 1): " + line);
+                }
+                bodies.add(new Body(fields[0], period, eccentricity, checkEpoch(LocalDate.parse(fields[3]))));
+            }
+            return bodies;
+        }
+
+        /*************************************************************
+         * Day counts are exact in a double far past any LocalDate, so *
+         * the angle can't quantize, but an epoch more than            *
+         * MAX_EPOCH_YEARS from the reference is taken for a mistake   *
+         * like +20200-08-03 instead of quietly giving a stray orbit.  *
+         *************************************************************/
+
+        public static LocalDate checkEpoch(LocalDate epoch) {
+            if (Math.abs(ChronoUnit.YEARS.between(REFERENCE_DATE, epoch)) > MAX_EPOCH_YEARS) {
+                throw new IllegalArgumentException("Epoch " + epoch + " is more than " + MAX_EPOCH_YEARS
+                        + " years from " + REFERENCE_DATE);
+            }
+            return epoch;
+        }
+
+        // True anomaly in degrees, Kepler's equation solved by a few Newton steps
+        public double position(LocalDate date) {
+            double days = ChronoUnit.DAYS.between(epoch, date);
+            double mean = Math.toRadians(((days * 360.0 / periodDays) % 360 + 360) % 360);
+            double eccentric = mean;
+            for (int i = 0; i < 10; i++) {
+                eccentric -= (eccentric - eccentricity * Math.sin(eccentric) - mean) / (1 - eccentricity * Math.cos(eccentric));
+            }
+            double trueAnomaly = 2 * Math.atan2(Math.sqrt(1 + eccentricity) * Math.sin(eccentric / 2),
+                    Math.sqrt(1 - eccentricity) * Math.cos(eccentric / 2));
+            return (Math.toDegrees(trueAnomaly) + 360) % 360;
+        }
+    }
+
+    /****************************************************************
+     * --grid, several bodies side by side. Every tile is a Spacerace *
+     * of its own built from the same command line, so each one      *
+     * twinkles with its own stars and follows its own orbit. What    *
+     * main loaded from files is shared, and the frames go out through *
+     * the host so --once and --record work the same as for one body. *
+     ****************************************************************/
+
+    public static final class MultiClock {
+        private static final int TILE_GAP = 2;
+        private final List<String> labels = new ArrayList<>();
+        private final List<Spacerace> clocks = new ArrayList<>();
+        private Spacerace host;
+
+        public MultiClock add(String label, Spacerace clock) {
+            labels.add(label);
+            clocks.add(clock);
+            return this;
+        }
+
+        public static MultiClock fromArgs(Spacerace host, String[] args, List<Body> bodies, String names, int width,
+                                          int height) throws IOException {
+            String[] picked = names.split(",");
+            int[][] cells = gridCells(picked.length, width, height);
+            MultiClock grid = new MultiClock();
+            grid.host = host;
+            for (int i = 0; i < picked.length; i++) {
+                Spacerace tile = new Spacerace();
+                tile.rowsToRemove = tile.ParseCmdLine(args);
+                tile.artArchive = host.artArchive;
+                tile.constellations = host.constellations;
+                tile.backgroundImage = host.backgroundImage;
+                tile.viewpoint = host.viewpoint;
+                // Every tile adds its own row to the one CSV log, in grid order
+                tile.csvWriter = host.csvWriter;
+                tile.body = findBody(bodies, picked[i].trim());
+                tile.orbitalPeriodDays = tile.body.periodDays;
+                tile.canvasWidth = cells[i][2];
+                tile.canvasHeight = cells[i][3];
+                tile.loadArtForDate(tile.currentDate());
+                grid.add(tile.body.name, tile);
+            }
+            return grid;
+        }
+
+        // Tiles as {left, top, width, height}, one row of the height goes to the label
+        public static int[][] gridCells(int count, int width, int height) {
+            int columns = (int) Math.ceil(Math.sqrt(count));
+            int rows = (count + columns - 1) / columns;
+            int tileWidth = Math.max(1, (width - TILE_GAP * (columns - 1)) / columns);
+            int tileHeight = Math.max(1, height / rows - 1);
+            int[][] cells = new int[count][];
+            for (int i = 0; i < count; i++) {
+                int column = i % columns;
+                int row = i / columns;
+                cells[i] = new int[]{column * (tileWidth + TILE_GAP), row * (tileHeight + 1), tileWidth, tileHeight};
+            }
+            return cells;
+        }
+
+        public List<String> nextFrame() {
+            int columns = (int) Math.ceil(Math.sqrt(clocks.size()));
+            List<String> frame = new ArrayList<>();
+            List<String> row = new ArrayList<>();
+            for (int i = 0; i < clocks.size(); i++) {
+                Spacerace clock = clocks.get(i);
+                List<String> tile = clock.nextFrame();
+                String label = String.format("%s %.2f°", labels.get(i), clock.currentPosition);
+                tile.add(0, label + " ".repeat(Math.max(0, clock.canvasWidth - label.length())));
+                row = row.isEmpty() ? tile : sideBySide(row, tile, TILE_GAP);
+                if ((i + 1) % columns == 0 || i == clocks.size() - 1) {
+                    frame.addAll(row);
+                    row = new ArrayList<>();
+                }
+            }
+            return frame;
+        }
+
+        public void run(long frameMillis) throws InterruptedException {
+            if (host.onceMode) {
+                host.writeFrame(nextFrame(), "", "");
+                return;
+            }
+            while (true) {
+                host.writeFrame(nextFrame(), CLEAR_SCREEN, "");
+                Thread.sleep(frameMillis);
+            }
+        }
+    }
+
+    /****************************************************************
+     * A constellation from the --constellations file, one per line  *
+     *   art-file name row,column row,column ...                     *
+     * naming the art it belongs to and the cells of its stars.      *
+     * Blank lines and lines starting with # are skipped.            *
+     ****************************************************************/
+
+    public static final class Constellation {
+        public final String artFile;
+        public final String name;
+        public final List<int[]> stars;
+
+        public Constellation(String artFile, String name, List<int[]> stars) {
+            this.artFile = artFile;
+            this.name = name;
+            this.stars = stars;
+        }
+
+        public static List<Constellation> fromConfig(List<String> lines) {
+            List<Constellation> constellations = new ArrayList<>();
+            for (String line : lines) {
+                String trimmed = line.trim();
+                if (trimmed.isEmpty() || trimmed.startsWith("#")) {
+                    continue;
+                }
+                String[] fields = trimmed.split("\\s+");
+                if (fields.length < 3) {
+                    throw new IllegalArgumentException("Expected art-file name row,column...: " + line);
+                }
+                List<int[]> stars = new ArrayList<>();
+                for (int i = 2; i < fields.length; i++) {
+                    String[] cell = fields[i].split(",");
+                    if (cell.length != 2) {
+                        throw new IllegalArgumentException("Expected row,column but got " + fields[i] + ": " + line);
+                    }
+                    stars.add(new int[]{Integer.parseInt(cell[0]), Integer.parseInt(cell[1])});
+                }
+                constellations.add(new Constellation(fields[0], fields[1], stars));
+            }
+            return constellations;
+        }
+
+        public boolean contains(int row, int column) {
+            for (int[] star : stars) {
+                if (star[0] == row && star[1] == column) {
+                    return true;
+                }
+            }
+            return false;
+        }
+    }
+
+    /****************************************************************
+     * The day's constellation out of those drawn in the given art,  *
+     * moving on to the next one every midnight. Null when the art   *
+     * has none.                                                     *
+     ****************************************************************/
+
+    public static Constellation constellationOfTheDay(List<Constellation> constellations, String artPath, LocalDate date) {
+        if (artPath == null) {
+            return null;
+        }
+        String artFile = Paths.get(artPath).getFileName().toString();
+        List<Constellation> drawn = new ArrayList<>();
+        for (Constellation constellation : constellations) {
+            if (constellation.artFile.equals(artFile)) {
+                drawn.add(constellation);
+            }
+        }
+        return drawn.isEmpty() ? null : drawn.get((int) Math.floorMod(date.toEpochDay(), (long) drawn.size()));
+    }
+
+    /****************************************************************
+     * --twinkle-gradient TOP:BOTTOM scales the twinkle chance from   *
+     * TOP on the first row to BOTTOM on the last, in a straight line *
+     * in between, for thinner air high up the sky. 1:1 is even.      *
+     ****************************************************************/
+
+    public static double rowIntensity(int row, int rows, double top, double bottom) {
+        if (rows <= 1) {
+            return top;
+        }
+        return top + (bottom - top) * row / (rows - 1);
+    }
+
+    // How much brighter the day's constellation is this frame, swelling up to CONSTELLATION_BOOST and back
+    public static double constellationPulse(long frame) {
+        return 1 + CONSTELLATION_BOOST * (0.5 - 0.5 * Math.cos(2 * Math.PI * frame / CONSTELLATION_PULSE_FRAMES));
+    }
+
+    public static Body findBody(List<Body> bodies, String name) {
+        for (Body body : bodies) {
+            if (body.name.equalsIgnoreCase(name)) {
+                return body;
+            }
+        }
+        throw new IllegalArgumentException("No body named " + name + " in the bodies file");
+    }
+
+    // The selected --body, the --calendar-year circle or the plain circle at the --period year length
+    public double orbitPosition(LocalDate date) {
+        if (body != null) {
+            return body.position(date);
+        }
+        return calendarYear ? calendarYearPosition(date) : calculateEarthPosition(date, orbitalPeriodDays);
+    }
+
+    /*****************************************************************
+     * --calendar-year goes around once per calendar year, 365 days   *
+     * in a common year and 366 in a leap year, so January 1st is     *
+     * always 0 degrees instead of drifting by a quarter day a year.  *
+     *****************************************************************/
+
+    public static double calendarYearPosition(LocalDate date) {
+        return (date.getDayOfYear() - 1) * 360.0 / date.lengthOfYear();
+    }
+
+    /*****************************************************************
+     * Same circle with any year length, a 50 day period makes the    *
+     * planet go all the way around in 50 days.                       *
+     *****************************************************************/
+
+    public static double calculateEarthPosition(LocalDate date, double orbitalPeriodDays) {
+        long daysBetween = ChronoUnit.DAYS.between(REFERENCE_DATE, date);
+
+        // Earth moves roughly 360/365.25 degrees per day along its orbit
+        return (daysBetween * 360.0 / orbitalPeriodDays) % 360;
+    }
+
+
+    /*************************
+     *   HEART OF ASCII ART   *
+     *************************/
+    public Spacerace(){
+
+        degreeArtMap.put(0,   "space010.txt");
+        degreeArtMap.put(20,  "space2030.txt");
+        degreeArtMap.put(40,  "space4050.txt");
+        degreeArtMap.put(60,  "space6070.txt");
+        degreeArtMap.put(90,  "space90110.txt");
+        degreeArtMap.put(120, "space120130.txt");
+        degreeArtMap.put(140, "space140150.txt");
+        degreeArtMap.put(160, "space160170.txt");
+        degreeArtMap.put(180, "space180190.txt");
+        degreeArtMap.put(200, "space200210.txt");
+        degreeArtMap.put(220, "space220230.txt");
+        degreeArtMap.put(240, "space240260.txt");
+        degreeArtMap.put(270, "space270290.txt");
+        degreeArtMap.put(300, "space300310.txt");
+        degreeArtMap.put(320, "space32030.txt");
+        degreeArtMap.put(340, "space34050.txt");
+        // No entry at FULL_ORBIT, the 340 art covers 340 up to 360 where the orbit wraps back to 0
+    }
+
+    // Read only view of the degree to art map
+    public NavigableMap<Integer, String> artMap() {
+        return Collections.unmodifiableNavigableMap(degreeArtMap);
+    }
+
+    /*************************************************
+     * Takes position calculated and finds the        *
+     * corresponding filepath in the Navigable Map    *
+     *************************************************/
+
+    public void loadAsciiArtForCurrentPosition(double position) {
+        // Load the ASCII art from the file path
+        try {
+            String filePath = resolveArtPath(position);
+            if (filePath == null) {
+                artLines = generateStarfield();
+            } else {
+                asciiArt(filePath);
+            }
+        } catch (IOException e) {
+            System.err.println("Error loading ASCII art: " + e.getMessage());
+        }
+    }
+
+    public String resolveArtPath(double position) {
+        if (degreeArtMap.isEmpty()) {
+            return null; // Nothing configured, callers fall back to a generated starfield
+        }
+        // Find the appropriate file for the current position
+        return artPath(degreeArtMap.get(bucketKey(degreeArtMap, position)));
+    }
+
+    public static int bucketKey(NavigableMap<Integer, String> map, double position) {
+        // Wrap into [0, FULL_ORBIT) so dates before the reference and the 340-360 span resolve cleanly
+        double angle = ((position % FULL_ORBIT) + FULL_ORBIT) % FULL_ORBIT;
+        if (angle >= map.lastKey()) {
+            return map.lastKey(); // The last bucket spans up to FULL_ORBIT, 340 to 360 in the default map
+        }
+        Integer key = map.floorKey((int) angle);
+        if (key == null) {
+            key = map.firstKey(); // Default to the first range if position is below the lowest key
+        }
+        return key;
+    }
+
+    /***************************************************************
+     * The angle printed on the art and in the status bar, the     *
+     * bucket's own angle once b has been pressed. The bucket comes *
+     * from artPosition, the angle that picked the art, so it names *
+     * the art on screen even with --viewpoint.                     *
+     ***************************************************************/
+
+    public static double shownAngle(double position, double artPosition, NavigableMap<Integer, String> map,
+                                    boolean bucket) {
+        return bucket && !map.isEmpty() ? bucketKey(map, artPosition) : position;
+    }
+
+    /***************************************************************
+     * Art for the far side of the orbit, where the earth will be  *
+     * six months from the given angle.                            *
+     ***************************************************************/
+
+    public String oppositeArtPath(double angle) {
+        return resolveArtPath((angle + 180) % 360);
+    }
+
+    // Relative to --art-root, an absolute path from --art is used as it is
+    private String artPath(String fileName) {
+        return Paths.get(artRoot).resolve(fileName).toString();
+    }
+
+    /***************************************************************
+     * One --art DEG=PATH mapping, the art shown from DEG degrees  *
+     * up to the next mapped angle. The first --art on a command   *
+     * line clears the built in map so only the given ones apply.  *
+     ***************************************************************/
+
+    public static void putArtMapping(NavigableMap<Integer, String> map, String mapping) {
+        String[] parts = mapping.split("=", 2);
+        if (parts.length != 2 || parts[1].isBlank()) {
+            throw new IllegalArgumentException("Expected --art DEG=PATH but got: " + mapping);
+        }
+        int degrees;
+        try {
+            degrees = Integer.parseInt(parts[0].trim());
+        } catch (NumberFormatException e) {
+            throw new IllegalArgumentException("--art angle must be a whole number of degrees: " + mapping);
+        }
+        if (degrees < 0 || degrees >= FULL_ORBIT) {
+            throw new IllegalArgumentException("--art angle must be in [0, " + FULL_ORBIT + "): " + mapping);
+        }
+        map.put(degrees, parts[1].trim());
+    }
+
+    /********************************************************
+     * Special case handling, solstices and equinoxes get    *
+     * their own art, every other day falls back to the map. *
+     ********************************************************/
+
+    public String resolveArtPathForDate(LocalDate date) {
+        if (viewpoint != null) {
+            return resolveArtPath(viewpoint.position(date)); // Earth's solstices mean nothing from there
+        }
+        String eventArt = seasonEventArt(date);
+        if (eventArt != null) {
+            return artPath(eventArt);
+        }
+        return resolveArtPath(orbitPosition(date));
+    }
+
+    /****************************************************************
+     * The scene only moves at midnight, so the next change is the   *
+     * first midnight whose date resolves to different art. Null     *
+     * when nothing changes within two orbits (an empty map).        *
+     ****************************************************************/
+
+    public Duration timeUntilNextBucket(ZonedDateTime from) {
+        String current = resolveArtPathForDate(from.toLocalDate());
+        LocalDate day = from.toLocalDate().plusDays(1);
+        LocalDate limit = day.plusDays((long) Math.ceil(orbitalPeriodDays * 2));
+        while (day.isBefore(limit)) {
+            if (!Objects.equals(resolveArtPathForDate(day), current)) {
+                return Duration.between(from, day.atStartOfDay(from.getZone()));
+            }
+            day = day.plusDays(1);
+        }
+        return null;
+    }
+
+    public List<BucketChange> bucketChangesIn(ZonedDateTime from, Duration window) {
+        List<BucketChange> changes = new ArrayList<>();
+        ZonedDateTime end = from.plus(window);
+        ZonedDateTime cursor = from;
+        Duration wait;
+        while ((wait = timeUntilNextBucket(cursor)) != null && !cursor.plus(wait).isAfter(end)) {
+            cursor = cursor.plus(wait);
+            changes.add(new BucketChange(cursor, resolveArtPathForDate(cursor.toLocalDate())));
+        }
+        return changes;
+    }
+
+    public static final class BucketChange {
+        public final ZonedDateTime time;
+        public final String artPath;
+
+        public BucketChange(ZonedDateTime time, String artPath) {
+            this.time = time;
+            this.artPath = artPath;
+        }
+    }
+
+    /*************************************************************
+     * Season the date falls in, named after the solstice or       *
+     * equinox that started it, and the date of the next one.      *
+     *************************************************************/
+
+    public static String season(LocalDate date) {
+        LocalDate day = date;
+        while (seasonEventArt(day) == null) {
+            day = day.minusDays(1);
+        }
+        switch (seasonEventArt(day)) {
+            case VERNAL_EQUINOX_PATH: return "Spring";
+            case SUMMER_SOLSTICE_PATH: return "Summer";
+            case AUTUMN_EQUINOX_PATH: return "Autumn";
+            default: return "Winter";
+        }
+    }
+
+    public static LocalDate nextSeasonEvent(LocalDate date) {
+        LocalDate day = date.plusDays(1);
+        while (seasonEventArt(day) == null) {
+            day = day.plusDays(1);
+        }
+        return day;
+    }
+
+    public static String seasonEventArt(LocalDate date) {
+        if (date.equals(LocalDate.of(date.getYear(), 12, 21))) {
+            return WINTER_SOLSTICE_PATH;
+        } else if (date.equals(LocalDate.of(date.getYear(), 3, 20))) {
+            return VERNAL_EQUINOX_PATH;
+        } else if (date.equals(LocalDate.of(date.getYear(), 6, 21))) {
+            return SUMMER_SOLSTICE_PATH;
+        } else if (date.equals(LocalDate.of(date.getYear(), 9, 22))) {
+            return AUTUMN_EQUINOX_PATH;
+        }
+        return null;
+    }
+
+    /******************************************************************
+     * Flashes and rings the bell once on a solstice or equinox. The   *
+     * same dates as the special art are used since 0 degrees is       *
+     * January 1st in this model, not an equinox. Once per event day.  *
+     ******************************************************************/
+
+    public boolean checkSeasonEvent(LocalDate date) {
+        if (demoMode || seasonEventArt(date) == null || date.equals(lastSeasonEvent)) {
+            return false;
+        }
+        lastSeasonEvent = date;
+        highlightFrames = HIGHLIGHT_FRAMES;
+        System.out.print("\007");
+        return true;
+    }
+
+    /*************************************************************
+     * SPACERACE_ANGLE for kiosks, a number of degrees that holds  *
+     * the scene still. Unset or not a number means use the clock. *
+     *************************************************************/
+
+    public static Double parseAngle(String value) {
+        if (value == null || value.isBlank()) {
+            return null;
+        }
+        try {
+            double angle = Double.parseDouble(value.trim());
+            return Double.isFinite(angle) ? angle : null;
+        } catch (NumberFormatException e) {
+            return null;
+        }
+    }
+
+    // Every bucket of the map in angle order, loadable or not
+    public List<Integer> artBuckets() {
+        return new ArrayList<>(degreeArtMap.keySet());
+    }
+
+    /****************************************************************
+     * Buckets --cycle and --random actually land on, the ones whose *
+     * art loads and has something in it. Worked out once, a later   *
+     * failed load drops that bucket too. With nothing playable all  *
+     * buckets stay in so the placeholder shows instead of a hang.   *
+     ****************************************************************/
+
+    public List<Integer> playableBuckets() {
+        if (playableBuckets == null) {
+            playableBuckets = new ArrayList<>();
+            for (int bucket : artBuckets()) {
+                try {
+                    if (!isBlankArt(readArtLines(artPath(degreeArtMap.get(bucket))).toArray(new String[0]))) {
+                        playableBuckets.add(bucket);
+                    }
+                } catch (IOException e) {
+                    // Missing or unreadable, skipped
+                }
+            }
+        }
+        return playableBuckets.isEmpty() ? artBuckets() : playableBuckets;
+    }
+
+    /****************************************************************
+     * Screensaver mode for --cycle, every bucket gets the same      *
+     * dwell time in map order and the last one wraps to the first.  *
+     * Otherwise the scene follows the (possibly virtual) date.      *
+     ****************************************************************/
+
+    public int cycleBucket(long frame) {
+        List<Integer> buckets = playableBuckets();
+        long period = frame / cycleDwellFrames;
+        if (!randomScenes) {
+            return buckets.get((int) (period % buckets.size()));
+        }
+        if (period != randomPeriod) {
+            randomPeriod = period;
+            randomBucket = pickBucket(buckets, bucketWeights, random);
+        }
+        return randomBucket;
+    }
+
+    /*************************************************************
+     * Weighted pick for --random, buckets without a weight count  *
+     * as 1 so a single favourite can be bumped on its own.        *
+     *************************************************************/
+
+    public static int pickBucket(List<Integer> buckets, Map<Integer, Integer> weights, Random random) {
+        int[] bucketWeights = new int[buckets.size()];
+        for (int i = 0; i < bucketWeights.length; i++) {
+            bucketWeights[i] = weights.getOrDefault(buckets.get(i), 1);
+        }
+        return buckets.get(pickWeighted(bucketWeights, random));
+    }
+
+    private boolean cycling() {
+        return cycleDwellFrames > 0 && !degreeArtMap.isEmpty();
+    }
+
+    public double scenePosition(LocalDate date) {
+        if (pinnedAngle != null) {
+            return pinnedAngle;
+        }
+        return cycling() ? cycleBucket(frameCount) : orbitPosition(date);
+    }
+
+    /*****************************************************************
+     * --viewpoint NAME shows the sky as seen from another body of the *
+     * bodies file, its place in its own orbit picks the art while the *
+     * angle shown stays the one of the orbit being followed.          *
+     *****************************************************************/
+
+    public double artPosition(LocalDate date) {
+        if (viewpoint != null && pinnedAngle == null && !cycling()) {
+            return viewpoint.position(date);
+        }
+        return scenePosition(date);
+    }
+
+    public String resolveScenePath(LocalDate date) {
+        if (demoMode) {
+            return null;
+        }
+        return pinnedAngle != null || cycling() ? resolveArtPath(scenePosition(date)) : resolveArtPathForDate(date);
+    }
+
+    /**************************************************************
+     * Loads the art for the date and keeps a pristine copy for    *
+     * the twinkling loop. Row removal from -m is reapplied here.  *
+     **************************************************************/
+
+    public void loadArtForDate(LocalDate date) throws IOException {
+        String filePath = resolveScenePath(date);
+        currentArtPath = filePath;
+        currentBlend = blendFraction(date);
+        IOException failure = null;
+        try {
+            loadScene(date, filePath);
+        } catch (IOException e) {
+            failure = e;
+            artLines = new String[0];
+        }
+        if (failure == null && filePath != null && isBlankArt(artLines)) {
+            // An empty mapped file would otherwise leave a blank screen with no hint as to why
+            System.err.println("Art file " + filePath + " is empty, showing the placeholder");
+            artLines = new String[0];
+        }
+        modifyArtLines(rowsToRemove);
+        if (ansiArt) {
+            liftAnsiColors();
+        }
+        if (borderless) {
+            stripOuterBorder();
+        }
+        if (flipHorizontal) {
+            flipArtHorizontally();
+        }
+        if (flipVertical) {
+            flipArtVertically();
+        }
+        if (asciiOnly) {
+            for (int i = 0; i < artLines.length; i++) {
+                artLines[i] = toAscii(artLines[i]);
+            }
+        }
+        if (artLines.length == 0) {
+            int[] size = frameSize();
+            artLines = placeholderArt(placeholder, scenePosition(date), size[0], size[1]);
+        }
+        if (backgroundImage != null) {
+            int[] size = artDimensions();
+            artLines = compositeOver(artLines, imageToAscii(backgroundImage, size[0], size[1], backgroundRamp));
+        }
+        originalArt = Arrays.copyOf(artLines, artLines.length);
+        starPhases = new double[artLines.length][];
+        starFrequencies = new double[artLines.length][];
+        for (int row = 0; row < artLines.length; row++) {
+            starPhases[row] = new double[artLines[row].length()];
+            starFrequencies[row] = new double[artLines[row].length()];
+            for (int col = 0; col < starPhases[row].length; col++) {
+                starPhases[row][col] = random.nextDouble();
+                if (shimmer) {
+                    double hertz = SHIMMER_MIN_HZ + random.nextDouble() * (SHIMMER_MAX_HZ - SHIMMER_MIN_HZ);
+                    starFrequencies[row][col] = 2 * Math.PI * hertz;
+                }
+            }
+        }
+        if (failure != null) {
+            throw failure;
+        }
+    }
+
+    public static boolean isBlankArt(String[] lines) {
+        for (String line : lines) {
+            if (!line.isBlank()) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /*************************************************************
+     * --background IMAGE, a photo turned into characters behind   *
+     * the stars. Each cell takes the average brightness of the    *
+     * pixels under it and picks from --ramp, darkest first. The   *
+     * image is scaled to the art, or the terminal for the         *
+     * generated starfield. Star glyphs and the OVER_WRITE markers *
+     * are kept out of the ramp so the backdrop stays put.         *
+     *************************************************************/
+
+    public static String[] imageToAscii(BufferedImage image, int width, int height, String ramp) {
+        String[] lines = new String[height];
+        for (int row = 0; row < height; row++) {
+            char[] cells = new char[width];
+            for (int col = 0; col < width; col++) {
+                int left = col * image.getWidth() / width;
+                int right = Math.max(left + 1, (col + 1) * image.getWidth() / width);
+                int top = row * image.getHeight() / height;
+                int bottom = Math.max(top + 1, (row + 1) * image.getHeight() / height);
+                double total = 0;
+                for (int y = top; y < bottom; y++) {
+                    for (int x = left; x < right; x++) {
+                        total += luminance(image.getRGB(x, y));
+                    }
+                }
+                double brightness = total / ((right - left) * (bottom - top));
+                cells[col] = ramp.charAt((int) Math.round(brightness * (ramp.length() - 1)));
+            }
+            lines[row] = new String(cells);
+        }
+        return lines;
+    }
+
+    // Relative luminance of a packed RGB pixel, 0 for black up to 1 for white
+    private static double luminance(int rgb) {
+        int red = (rgb >> 16) & 0xFF;
+        int green = (rgb >> 8) & 0xFF;
+        int blue = rgb & 0xFF;
+        return (0.2126 * red + 0.7152 * green + 0.0722 * blue) / 255;
+    }
+
+    // The art on top, the backdrop showing through wherever the art has a space
+    public static String[] compositeOver(String[] art, String[] backdrop) {
+        String[] composed = new String[Math.max(art.length, backdrop.length)];
+        for (int row = 0; row < composed.length; row++) {
+            String top = row < art.length ? art[row] : "";
+            String bottom = row < backdrop.length ? backdrop[row] : "";
+            char[] cells = new char[Math.max(top.length(), bottom.length())];
+            for (int col = 0; col < cells.length; col++) {
+                char c = col < top.length() ? top.charAt(col) : ' ';
+                cells[col] = c == ' ' && col < bottom.length() ? bottom.charAt(col) : c;
+            }
+            composed[row] = new String(cells);
+        }
+        return composed;
+    }
+
+    private void loadScene(LocalDate date, String filePath) throws IOException {
+        if (demoMode) {
+            // Same sky every run so rendering changes can be compared by eye
+            artLines = generateStarfield(new Random(DEMO_SEED));
+        } else if (filePath == null) {
+            artLines = generateStarfield();
+        } else {
+            asciiArt(filePath);
+        }
+        if (currentBlend > 0) {
+            String[] outgoing = artLines;
+            int nextKey = nextBucketKey(artPosition(date));
+            try {
+                asciiArt(artPath(degreeArtMap.get(nextKey)));
+                // Seeded by the bucket so the same cells stay swapped from frame to frame
+                artLines = blendArt(outgoing, artLines, currentBlend, new Random(nextKey));
+            } catch (IOException e) {
+                // The next bucket is broken, keep showing this one unblended until the crossing
+                artLines = outgoing;
+            }
+        }
+        if (showOpposite) {
+            appendOpposite(date);
+        }
+    }
+
+    /*************************************************************
+     * Stand in for art that failed to load or came up empty, the  *
+     * --placeholder text centered in the frame with {angle}       *
+     * filled in with the whole degrees.                           *
+     *************************************************************/
+
+    public static String[] placeholderArt(String text, double angle, int width, int height) {
+        long degrees = Math.round(((angle % FULL_ORBIT) + FULL_ORBIT) % FULL_ORBIT) % FULL_ORBIT;
+        String message = text.replace("{angle}", String.format("%03d", degrees));
+        String[] lines = new String[Math.max(1, height)];
+        Arrays.fill(lines, " ".repeat(Math.max(width, message.length())));
+        int left = Math.max(0, (width - message.length()) / 2);
+        lines[lines.length / 2] = " ".repeat(left) + message + " ".repeat(Math.max(0, width - left - message.length()));
+        return lines;
+    }
+
+    /*************************************************************
+     * --borderless drops the frame around the art, the top and    *
+     * bottom rows when they are all box-drawing and the box char  *
+     * at either end of every other row, so the stars run edge to  *
+     * edge. Boxes inside the art, like the text panel, stay.      *
+     *************************************************************/
+
+    private void stripOuterBorder() {
+        int first = 0;
+        int last = artLines.length;
+        if (last > first && isBorderRow(artLines[first])) {
+            first++;
+        }
+        if (last > first && isBorderRow(artLines[last - 1])) {
+            last--;
+        }
+        artLines = Arrays.copyOfRange(artLines, first, last);
+        if (artColors != null) {
+            artColors = Arrays.copyOfRange(artColors, first, last);
+        }
+        for (int row = 0; row < artLines.length; row++) {
+            String line = artLines[row];
+            int start = line.length() > 0 && isBoxChar(line.charAt(0)) ? 1 : 0;
+            int end = line.length() > start && isBoxChar(line.charAt(line.length() - 1)) ? line.length() - 1 : line.length();
+            artLines[row] = line.substring(start, end);
+            if (artColors != null && artColors[row] != null) {
+                artColors[row] = Arrays.copyOfRange(artColors[row], start, Math.min(end, artColors[row].length));
+            }
+        }
+    }
+
+    /*************************************************************
+     * --flip-h mirrors each row and --flip-v turns the rows upside *
+     * down, swapping the box-drawing pieces so frames still join.  *
+     * Words, and the room after them for what OVER_WRITE stamps,   *
+     * are moved as a block so the text panel stays readable.       *
+     *************************************************************/
+
+    private static final String MIRROR_H = "┌┐└┘├┤╭╮╰╯╔╗╚╝╠╣┏┓┗┛┣┫╱╲/\\()[]<>{}";
+    private static final String MIRROR_V = "┌└┐┘┬┴╭╰╮╯╔╚╗╝╦╩┏┗┓┛┳┻╱╲/\\";
+
+    private void flipArtHorizontally() {
+        for (int row = 0; row < artLines.length; row++) {
+            int[] order = mirroredOrder(artLines[row]);
+            char[] flipped = new char[order.length];
+            for (int k = 0; k < order.length; k++) {
+                // Words come out in reading order and keep their brackets as they are
+                char c = artLines[row].charAt(Math.abs(order[k]) - 1);
+                flipped[k] = order[k] < 0 ? c : swapPair(MIRROR_H, c);
+                order[k] = Math.abs(order[k]) - 1;
+            }
+            artLines[row] = new String(flipped);
+            if (artColors != null && artColors[row] != null && artColors[row].length == order.length) {
+                int[][] colors = new int[order.length][];
+                for (int k = 0; k < order.length; k++) {
+                    colors[k] = artColors[row][order[k]];
+                }
+                artColors[row] = colors;
+            }
+        }
+    }
+
+    private void flipArtVertically() {
+        List<String> rows = new ArrayList<>(Arrays.asList(artLines));
+        Collections.reverse(rows);
+        artLines = rows.toArray(new String[0]);
+        for (int row = 0; row < artLines.length; row++) {
+            StringBuilder flipped = new StringBuilder(artLines[row]);
+            for (int source : mirroredOrder(artLines[row])) {
+                if (source > 0) {
+                    flipped.setCharAt(source - 1, swapPair(MIRROR_V, flipped.charAt(source - 1)));
+                }
+            }
+            artLines[row] = flipped.toString();
+        }
+        if (artColors != null) {
+            List<int[][]> colors = new ArrayList<>(Arrays.asList(artColors));
+            Collections.reverse(colors);
+            artColors = colors.toArray(new int[0][][]);
+        }
+    }
+
+    /*************************************************************
+     * Where each column of a mirrored row comes from. The row is  *
+     * cut into runs, a word with the spaces after it is one run   *
+     * kept in reading order, and the runs are laid out backwards. *
+     * Columns are 1 based, negative when they belong to a word.   *
+     *************************************************************/
+
+    public static int[] mirroredOrder(String line) {
+        List<int[]> runs = new ArrayList<>();
+        int i = 0;
+        while (i < line.length()) {
+            int end = i + 1;
+            boolean word = false;
+            char c = line.charAt(i);
+            if (c == ' ') {
+                while (end < line.length() && line.charAt(end) == ' ') {
+                    end++;
+                }
+            } else if (!isBoxChar(c)) {
+                while (end < line.length() && line.charAt(end) != ' ' && !isBoxChar(line.charAt(end))) {
+                    end++;
+                }
+                for (int k = i; k < end && !word; k++) {
+                    word = isTextChar(line.charAt(k));
+                }
+                // A sentence is one run, words only a single space apart stay together
+                while (word && end + 1 < line.length() && line.charAt(end) == ' '
+                        && line.charAt(end + 1) != ' ' && !isBoxChar(line.charAt(end + 1))) {
+                    end += 2;
+                    while (end < line.length() && line.charAt(end) != ' ' && !isBoxChar(line.charAt(end))) {
+                        end++;
+                    }
+                }
+                while (word && end < line.length() && line.charAt(end) == ' ') {
+                    end++;
+                }
+            }
+            runs.add(new int[]{i, end, word ? 1 : 0});
+            i = end;
+        }
+        int[] order = new int[line.length()];
+        int column = 0;
+        for (int r = runs.size() - 1; r >= 0; r--) {
+            int[] run = runs.get(r);
+            for (int k = 0; k < run[1] - run[0]; k++) {
+                order[column++] = run[2] == 1 ? -(run[0] + k + 1) : run[1] - k;
+            }
+        }
+        return order;
+    }
+
+    private static boolean isTextChar(char c) {
+        return Character.isLetterOrDigit(c) || c == '&' || c == '=' || c == '#';
+    }
+
+    // The other half of c's pair in a string of pairs, or c itself
+    private static char swapPair(String pairs, char c) {
+        int index = pairs.indexOf(c);
+        if (index < 0) {
+            return c;
+        }
+        return pairs.charAt(index % 2 == 0 ? index + 1 : index - 1);
+    }
+
+    private static boolean isBorderRow(String line) {
+        return !line.isBlank() && line.chars().allMatch(c -> c == ' ' || isBoxChar((char) c));
+    }
+
+    private static boolean isBoxChar(char c) {
+        return c >= '\u2500' && c <= '\u257F';
+    }
+
+    /*************************************************************
+     * Strips the escapes out of artLines so columns line up with  *
+     * what is drawn, keeping the colors aside for styleLine.      *
+     *************************************************************/
+
+    private void liftAnsiColors() {
+        artColors = new int[artLines.length][][];
+        for (int row = 0; row < artLines.length; row++) {
+            StringBuilder plain = new StringBuilder();
+            List<int[]> colors = new ArrayList<>();
+            for (Span span : parseAnsiLine(artLines[row])) {
+                plain.append(span.text);
+                for (int k = 0; k < span.text.length(); k++) {
+                    colors.add(span.color);
+                }
+            }
+            artLines[row] = plain.toString();
+            artColors[row] = colors.toArray(new int[0][]);
+        }
+    }
+
+    /*************************************************************
+     * Splits a line on its SGR escapes into colored spans. Knows  *
+     * the 16 and 256 color codes and 24 bit RGB, other escapes    *
+     * are dropped without changing the color.                     *
+     *************************************************************/
+
+    public static List<Span> parseAnsiLine(String line) {
+        List<Span> spans = new ArrayList<>();
+        StringBuilder text = new StringBuilder();
+        int[] color = null;
+        int i = 0;
+        while (i < line.length()) {
+            char c = line.charAt(i);
+            if (c != '\033' || i + 1 >= line.length() || line.charAt(i + 1) != '[') {
+                text.append(c);
+                i++;
+                continue;
+            }
+            int end = i + 2;
+            while (end < line.length() && (line.charAt(end) < '@' || line.charAt(end) > '~')) {
+                end++;
+            }
+            if (end == line.length()) {
+                break;
+            }
+            if (line.charAt(end) == 'm') {
+                int[] next = sgrColor(line.substring(i + 2, end), color);
+                if (!Arrays.equals(next, color) && text.length() > 0) {
+                    spans.add(new Span(text.toString(), color));
+                    text.setLength(0);
+                }
+                color = next;
+            }
+            i = end + 1;
+        }
+        if (text.length() > 0) {
+            spans.add(new Span(text.toString(), color));
+        }
+        return spans;
+    }
+
+    private static int[] sgrColor(String params, int[] color) {
+        String[] codes = params.isEmpty() ? new String[]{"0"} : params.split(";");
+        for (int k = 0; k < codes.length; k++) {
+            int code = codes[k].isEmpty() ? 0 : sgrNumber(codes[k]);
+            if (code == 0 || code == 39) {
+                color = null;
+            } else if (code >= 30 && code <= 37) {
+                color = ANSI16_COLORS[code - 30];
+            } else if (code >= 90 && code <= 97) {
+                color = ANSI16_COLORS[code - 90 + 8];
+            } else if (code == 38 && k + 2 < codes.length && codes[k + 1].equals("5")) {
+                int index = sgrNumber(codes[k + 2]);
+                if (index >= 0) {
+                    color = ansi256ToRgb(index);
+                }
+                k += 2;
+            } else if (code == 38 && k + 4 < codes.length && codes[k + 1].equals("2")) {
+                int[] rgb = {sgrNumber(codes[k + 2]), sgrNumber(codes[k + 3]), sgrNumber(codes[k + 4])};
+                if (rgb[0] >= 0 && rgb[1] >= 0 && rgb[2] >= 0) {
+                    color = rgb;
+                }
+                k += 4;
+            }
+        }
+        return color;
+    }
+
+    // A color parameter 0..255, or -1 for anything else so the code is dropped
+    private static int sgrNumber(String param) {
+        try {
+            int value = Integer.parseInt(param);
+            return value >= 0 && value <= 255 ? value : -1;
+        } catch (NumberFormatException e) {
+            return -1;
+        }
+    }
+
+    public static int[] ansi256ToRgb(int index) {
+        if (index < 0 || index > 255) {
+            throw new IllegalArgumentException("Not a 256 color index: " + index);
+        } else if (index < 16) {
+            return ANSI16_COLORS[index];
+        } else if (index < 232) {
+            int cube = index - 16;
+            int[] levels = {0, 95, 135, 175, 215, 255};
+            return new int[]{levels[cube / 36], levels[cube / 6 % 6], levels[cube % 6]};
+        }
+        int grey = 8 + (index - 232) * 10;
+        return new int[]{grey, grey, grey};
+    }
+
+    /*******************************************************************
+     * How far into the --blend margin before the next bucket the date  *
+     * is, 0 outside the margin rising toward 1 at the boundary. Special *
+     * days and cycle mode always show a single scene.                  *
+     *******************************************************************/
+
+    public double blendFraction(LocalDate date) {
+        if (blendMargin <= 0 || cycling() || degreeArtMap.isEmpty() || (viewpoint == null && seasonEventArt(date) != null)) {
+            return 0;
+        }
+        double toBoundary = degreesToNextBucket(artPosition(date));
+        return toBoundary >= blendMargin ? 0 : 1 - toBoundary / blendMargin;
+    }
+
+    private double degreesToNextBucket(double position) {
+        double angle = ((position % FULL_ORBIT) + FULL_ORBIT) % FULL_ORBIT;
+        int nextKey = nextBucketKey(angle);
+        return (nextKey <= angle ? nextKey + FULL_ORBIT : nextKey) - angle;
+    }
+
+    /****************************************************************
+     * Reads the next bucket's file on a background thread once the  *
+     * orbit is within PREFETCH_MARGIN of it, so the crossing itself *
+     * only has to pick the lines out of artCache.                   *
+     ****************************************************************/
+
+    public void prefetchNextBucket(LocalDate date) {
+        if (cycling() || pinnedAngle != null || degreeArtMap.isEmpty()) {
+            return;
+        }
+        double position = artPosition(date);
+        if (degreesToNextBucket(position) > PREFETCH_MARGIN) {
+            return;
+        }
+        String filePath = artPath(degreeArtMap.get(nextBucketKey(position)));
+        if (filePath.equals(prefetchPath) || filePath.equals(currentArtPath)) {
+            return;
+        }
+        prefetchPath = filePath;
+        Thread prefetchThread = new Thread(() -> {
+            try {
+                artCache.put(filePath, readArtLines(filePath));
+            } catch (IOException e) {
+                // The load at the crossing reports it
+            }
+        });
+        prefetchThread.setDaemon(true);
+        prefetchThread.start();
+    }
+
+    // Whether a file's lines are waiting in artCache for the next asciiArt
+    public boolean isPrefetched(String filePath) {
+        return artCache.containsKey(filePath);
+    }
+
+    private int nextBucketKey(double angle) {
+        Integer next = degreeArtMap.higherKey((int) (((angle % FULL_ORBIT) + FULL_ORBIT) % FULL_ORBIT));
+        return next != null ? next : degreeArtMap.firstKey();
+    }
+
+    /*****************************************************************
+     * Mixes two scenes cell by cell, each cell comes from the        *
+     * incoming art with the given probability and from the outgoing  *
+     * art otherwise. Short lines are treated as padded with spaces.  *
+     *****************************************************************/
+
+    public static String[] blendArt(String[] outgoing, String[] incoming, double fraction, Random random) {
+        String[] blended = new String[Math.max(outgoing.length, incoming.length)];
+        for (int row = 0; row < blended.length; row++) {
+            String from = row < outgoing.length ? outgoing[row] : "";
+            String to = row < incoming.length ? incoming[row] : "";
+            char[] cells = new char[Math.max(from.length(), to.length())];
+            for (int col = 0; col < cells.length; col++) {
+                char a = col < from.length() ? from.charAt(col) : ' ';
+                char b = col < to.length() ? to.charAt(col) : ' ';
+                cells[col] = random.nextDouble() < fraction ? b : a;
+            }
+            blended[row] = new String(cells);
+        }
+        return blended;
+    }
+
+    /*******************************************************************
+     * Split screen for --opposite, the art six months ahead is placed  *
+     * to the right of today's. Its markers are stamped here with its   *
+     * own angle and date since OVER_WRITE only fills the first marker. *
+     *******************************************************************/
+
+    private void appendOpposite(LocalDate date) {
+        String[] current = artLines;
+        double position = artPosition(date);
+        String oppositePath = oppositeArtPath(position);
+        if (oppositePath == null) {
+            return;
+        }
+        try {
+            asciiArt(oppositePath);
+        } catch (IOException e) {
+            // Today's art loaded fine, it is shown on its own
+            System.err.println("Error loading the opposite art: " + e.getMessage());
+            artLines = current;
+            return;
+        }
+        String[] right = artLines;
+        String oppositeAngle = String.valueOf((position + 180) % 360);
+        String oppositeDate = date.plusMonths(6).format(DateTimeFormatter.ofPattern("yyyy-MM-dd"));
+        int width = 0;
+        for (String line : current) {
+            width = Math.max(width, line.length());
+        }
+        String[] combined = new String[Math.max(current.length, right.length)];
+        for (int i = 0; i < combined.length; i++) {
+            String left = i < current.length ? current[i] : "";
+            String other = i < right.length ? right[i] : "";
+            other = replaceSubstring(other, "&", oppositeAngle);
+            other = replaceSubstring(other, "=", oppositeDate);
+            other = replaceSubstring(other, "#", "+6 months");
+            combined[i] = left + " ".repeat(width - left.length() + 2) + other;
+        }
+        artLines = combined;
+    }
+
+    /******************************************************************
+     * Called every frame, when the orbit crosses into a new bucket    *
+     * the art is reloaded and the highlight timer is started.         *
+     ******************************************************************/
+
+    public boolean checkBucketCrossing(LocalDate date) {
+        String filePath = resolveScenePath(date);
+        boolean crossed = !Objects.equals(filePath, currentArtPath);
+        if (!crossed && blendFraction(date) == currentBlend) {
+            return false;
+        }
+        try {
+            loadArtForDate(date);
+        } catch (IOException e) {
+            if (cycling() && playableBuckets != null) {
+                playableBuckets.remove(Integer.valueOf(cycleBucket(frameCount)));
+            }
+            return false; // The placeholder is up in its place
+        }
+        if (!crossed) {
+            return false; // Same bucket, only the blend moved on
+        }
+        startColorTransition(palette);
+        highlightFrames = HIGHLIGHT_FRAMES;
+        if (bellOnChange) {
+            System.out.print("\007");
+        }
+        return true;
+    }
+
+    /****************************************************************
+     * Procedural fallback for when the map has no entries. A sparse *
+     * field of stars sized to the canvas, or to the terminal.       *
+     ****************************************************************/
+
+    public String[] generateStarfield() {
+        return generateStarfield(random);
+    }
+
+    public String[] generateStarfield(Random random) {
+        int[] size = frameSize();
+        int width = size[0];
+        int height = size[1];
+        String[] field = new String[height];
+        for (int row = 0; row < height; row++) {
+            char[] line = new char[width];
+            for (int col = 0; col < width; col++) {
+                float roll = random.nextFloat();
+                line[col] = roll < 0.003 ? '┼' : roll < 0.03 ? '*' : ' ';
+            }
+            field[row] = new String(line);
+        }
+        return field;
+    }
+
+    // The canvas from --width/--height, the terminal fills in whatever was left out
+    private int[] frameSize() {
+        int[] terminal = canvasWidth > 0 && canvasHeight > 0 ? null : terminalSize();
+        return new int[]{canvasWidth > 0 ? canvasWidth : terminal[0], canvasHeight > 0 ? canvasHeight : terminal[1]};
+    }
+
+    /****************************************************************
+     * --doctor, everything worth knowing when the display looks     *
+     * wrong. Only critical checks failing make the exit non-zero,   *
+     * the rest are reported as warnings.                            *
+     ****************************************************************/
+
+    public static final class Check {
+        public final String name;
+        public final boolean passed;
+        public final boolean critical;
+        public final String detail;
+
+        public Check(String name, boolean passed, boolean critical, String detail) {
+            this.name = name;
+            this.passed = passed;
+            this.critical = critical;
+            this.detail = detail;
+        }
+
+        @Override
+        public String toString() {
+            return (passed ? "PASS" : critical ? "FAIL" : "WARN") + "  " + name + ": " + detail;
+        }
+    }
+
+    public List<Check> doctor() {
+        List<Check> checks = new ArrayList<>();
+        boolean console = System.console() != null;
+        checks.add(new Check("terminal", console, false, console ? "attached" : "not a terminal, keys are disabled"));
+        String rawMode;
+        try {
+            rawMode = console ? stty("-g").trim() : "";
+        } catch (IOException | InterruptedException e) {
+            rawMode = "";
+        }
+        checks.add(new Check("raw mode", !rawMode.isEmpty(), false, rawMode.isEmpty() ? "stty unavailable" : "stty works"));
+        String size = null;
+        try {
+            size = console ? stty("size") : null;
+        } catch (IOException | InterruptedException e) {
+            // Reported as the default size below
+        }
+        int[] resolved = resolveTerminalSize(size);
+        checks.add(new Check("size", resolved != DEFAULT_TERMINAL_SIZE, false,
+                resolved[0] + "x" + resolved[1] + (resolved == DEFAULT_TERMINAL_SIZE ? " (default)" : "")));
+        checks.add(new Check("color", true, false, colorEnabled ? colorMode.name().toLowerCase() : "off (NO_COLOR)"));
+        checks.add(artArchive != null ? archiveCheck() : artRootCheck());
+        List<String> gaps = mapGaps(degreeArtMap, maxGapDegrees);
+        checks.add(new Check("art gaps", gaps.isEmpty(), false,
+                gaps.isEmpty() ? "none wider than " + maxGapDegrees + " degrees" : String.join("; ", gaps)));
+        LocalDate date = currentDate();
+        try {
+            loadArtForDate(date);
+            checks.add(new Check("scene", true, true, String.format("%.2f degrees, %s", scenePosition(date),
+                    currentArtPath == null ? "generated starfield" : currentArtPath)));
+        } catch (IOException e) {
+            checks.add(new Check("scene", false, true, "can't load " + currentArtPath + ": " + e.getMessage()));
+        }
+        return checks;
+    }
+
+    private Check artRootCheck() {
+        try {
+            List<String> report = scanArtDirectory(artRoot);
+            long missing = report.stream().filter(line -> line.startsWith("Missing")).count();
+            return new Check("art map", missing == 0, true, missing == 0
+                    ? degreeArtMap.size() + " entries, " + (report.size() - missing) + " unmapped file(s)"
+                    : missing + " mapped file(s) missing from " + artRoot);
+        } catch (IOException e) {
+            return new Check("art map", false, true, "can't read " + artRoot + ": " + e.getMessage());
+        }
+    }
+
+    // With --art-archive the mapped files are looked up in the zip and its entries are listed
+    private Check archiveCheck() {
+        List<String> missing = new ArrayList<>();
+        for (String fileName : new TreeSet<>(degreeArtMap.values())) {
+            if (archiveEntry(artPath(fileName)) == null) {
+                missing.add(fileName);
+            }
+        }
+        List<String> entries = new ArrayList<>();
+        artArchive.stream().filter(entry -> !entry.isDirectory()).forEach(entry -> entries.add(entry.getName()));
+        String contents = artArchive.getName() + " holds " + entries.size() + " file(s): " + String.join(", ", entries);
+        return new Check("art map", missing.isEmpty(), true, missing.isEmpty()
+                ? degreeArtMap.size() + " entries, " + contents
+                : missing.size() + " mapped file(s) missing (" + String.join(", ", missing) + "), " + contents);
+    }
+
+    /****************************************************************
+     * Stretches of the orbit one piece of art has to cover for more  *
+     * than --max-gap degrees, the wrap from the last bucket back to  *
+     * 0 included. Warned about on startup so a hole left in a custom *
+     * map gets noticed, --max-gap 25 flags the built in 60 to 90.    *
+     ****************************************************************/
+
+    public static List<String> mapGaps(NavigableMap<Integer, String> map, int maxGap) {
+        List<String> gaps = new ArrayList<>();
+        for (int key : map.keySet()) {
+            Integer next = map.higherKey(key);
+            int end = next != null ? next : map.firstKey() + FULL_ORBIT;
+            if (end - key > maxGap) {
+                gaps.add("No art between " + key + "° and " + (end % FULL_ORBIT) + "°, " + map.get(key)
+                        + " covers " + (end - key) + " degrees");
+            }
+        }
+        return gaps;
+    }
+
+    public static int doctorExitCode(List<Check> checks) {
+        for (Check check : checks) {
+            if (check.critical && !check.passed) {
+                return EXIT_FAILURE;
+            }
+        }
+        return EXIT_OK;
+    }
+
+    /*******************************************************************
+     * Reconciliation report for --scan. Lists art files nobody maps    *
+     * to and map entries whose file is missing from the art directory. *
+     *******************************************************************/
+
+    public List<String> scanArtDirectory(String artRoot) throws IOException {
+        Set<String> referenced = new HashSet<>(degreeArtMap.values());
+        referenced.add(WINTER_SOLSTICE_PATH);
+        referenced.add(VERNAL_EQUINOX_PATH);
+        referenced.add(SUMMER_SOLSTICE_PATH);
+        referenced.add(AUTUMN_EQUINOX_PATH);
+
+        Set<String> onDisk = new TreeSet<>();
+        try (DirectoryStream<Path> files = Files.newDirectoryStream(Paths.get(artRoot))) {
+            for (Path file : files) {
+                if (Files.isRegularFile(file)) {
+                    onDisk.add(file.getFileName().toString());
+                }
+            }
+        }
+
+        List<String> report = new ArrayList<>();
+        for (String fileName : onDisk) {
+            if (!referenced.contains(fileName)) {
+                report.add("Unmapped file: " + artRoot + "/" + fileName);
+            }
+        }
+        for (Map.Entry<Integer, String> entry : degreeArtMap.entrySet()) {
+            if (!Files.isRegularFile(Paths.get(artPath(entry.getValue())))) {
+                report.add("Missing file for " + entry.getKey() + " degrees: " + artPath(entry.getValue()));
+            }
+        }
+        return report;
+    }
+
+    /********************************************
+     * Creates the array from the file path      *
+     * specified. Needed for twinkling and clock. *
+     ********************************************/
+
+    public void asciiArt(String filePath) throws IOException {
+        List<String> lines = artCache.remove(filePath);
+        if (lines == null) {
+            lines = readArtLines(filePath);
+        }
+        artLines = new String[lines.size()];
+        for (int i = 0; i < artLines.length; i++) {
+            String line = expandTabs(lines.get(i), tabWidth);
+            artLines[i] = trimTrailing ? line.stripTrailing() : line;
+        }
+    }
+
+    private List<String> readArtLines(String filePath) throws IOException {
+        if (artArchive != null) {
+            return readArchiveEntry(filePath);
+        }
+        long size = Files.size(Paths.get(filePath));
+        if (size > maxArtBytes) {
+            throw new ArtTooLargeException(filePath, size, maxArtBytes);
+        }
+        return normalizeLines(Files.readString(Paths.get(filePath)));
+    }
+
+    /*************************************************************
+     * Windows art comes with \r\n, and a stray \r left at the end  *
+     * of a line sends the cursor back to column 0 mid frame. Both *
+     * are dropped so only \n ends a line.                         *
+     *************************************************************/
+
+    public static List<String> normalizeLines(String content) {
+        String text = content.replace("\r\n", "\n").replace("\r", "");
+        List<String> lines = new ArrayList<>(Arrays.asList(text.split("\n", -1)));
+        if (lines.get(lines.size() - 1).isEmpty()) {
+            lines.remove(lines.size() - 1);
+        }
+        return lines;
+    }
+
+    /*************************************************************
+     * Tabs go to the next multiple of width so the art lines up   *
+     * the same no matter how the terminal would have expanded it. *
+     *************************************************************/
+
+    public static String expandTabs(String line, int width) {
+        if (line.indexOf('\t') < 0) {
+            return line;
+        }
+        StringBuilder expanded = new StringBuilder();
+        for (char c : line.toCharArray()) {
+            if (c == '\t') {
+                expanded.append(" ".repeat(width - expanded.length() % width));
+            } else {
+                expanded.append(c);
+            }
+        }
+        return expanded.toString();
+    }
+
+    /***************************************************************
+     * Reads a mapped path out of the --art-archive zip. Entries may *
+     * keep the art directory ("asciiArt/space010.txt") or sit at    *
+     * the top of the archive ("space010.txt").                      *
+     ***************************************************************/
+
+    public List<String> readArchiveEntry(String filePath) throws IOException {
+        ZipEntry entry = archiveEntry(filePath);
+        if (entry == null) {
+            throw new NoSuchFileException(filePath, null, "not found in " + artArchive.getName());
+        }
+        if (entry.getSize() > maxArtBytes) {
+            throw new ArtTooLargeException(filePath, entry.getSize(), maxArtBytes);
+        }
+        try (InputStream in = artArchive.getInputStream(entry)) {
+            // Streamed entries report a size of -1, so the limit is enforced on the bytes read too
+            byte[] content = in.readNBytes((int) Math.min(maxArtBytes + 1, Integer.MAX_VALUE));
+            if (content.length > maxArtBytes) {
+                throw new ArtTooLargeException(filePath, maxArtBytes);
+            }
+            return normalizeLines(new String(content, StandardCharsets.UTF_8));
+        }
+    }
+
+    private ZipEntry archiveEntry(String filePath) {
+        ZipEntry entry = artArchive.getEntry(filePath.replace('\\', '/'));
+        if (entry == null) {
+            entry = artArchive.getEntry(Paths.get(filePath).getFileName().toString());
+        }
+        return entry;
+    }
+
+    public void openArtArchive(String path) throws IOException {
+        artArchive = new ZipFile(path);
+    }
+
+    /************************************************************
+     * Raised instead of reading a file past --max-art-bytes, a  *
+     * broken or huge file should not eat all the memory.        *
+     ************************************************************/
+
+    public static class ArtTooLargeException extends IOException {
+        private static final long serialVersionUID = 1L;
+
+        public ArtTooLargeException(String filePath, long size, long limit) {
+            super(filePath + " is " + size + " bytes, over the " + limit + " byte limit");
+        }
+
+        // For streams whose size isn't known up front, only that they ran past the limit
+        public ArtTooLargeException(String filePath, long limit) {
+            super(filePath + " is over the " + limit + " byte limit");
+        }
+    }
+
+    /********************************
+     * Starts the twinkling function.*
+     ********************************/
+    public void startTwinkling() {
+        Thread twinklingThread = new Thread(() -> {
+            try {
+                twinklingEffect();
+            } catch (InterruptedException e) {
+                Thread.currentThread().interrupt();
+            }
+        });
+        twinklingThread.start();
+    }
+
+    /************************************************************************************
+     * Searches for my target characters which represent stars and slowly replaces them *
+     * with different characters to simulate twinkling. The clear screen function is    *
+     * called to keep the presentation focused on the current character array.          *
+     ***********************************************************************************/
+
+
+    public void twinklingEffect() throws InterruptedException {
+        runWithHook(state -> { });
+    }
+
+    /*************************************************************
+     * The twinkling loop for embedders, the hook is handed the    *
+     * state of each frame right after it is drawn so neighbouring *
+     * widgets can follow along. Paused frames don't call it.      *
+     *************************************************************/
+
+    public void runWithHook(Consumer<RenderState> hook) throws InterruptedException {
+        //infinite loop, the stars will shine until the program is terminated
+        while (true) {
+            if (!paused) {
+                renderFrame();
+                hook.accept(state());
+            }
+            long interval = frameInterval(frameMillis, lastInput, clock.instant(), idleAfter);
+            Thread.sleep(clampInterval(interval, minFrameMillis)); // Adjust for desired speed
+        }
+    }
+
+    /*************************************************************
+     * Everything about the frame just drawn in one place, the     *
+     * angle on screen and the bucket it falls in (null with no    *
+     * map), the art file, the season and the moon's phase from 0  *
+     * at new moon, for hooks and for checking a render.           *
+     *************************************************************/
+
+    public static final class RenderState {
+        public final double angle;
+        public final Integer bucketKey;
+        public final String artPath;
+        public final long frame;
+        public final String season;
+        public final double moonPhase;
+        public final int artWidth;
+        public final int artHeight;
+
+        public RenderState(double angle, Integer bucketKey, String artPath, long frame, String season,
+                           double moonPhase, int artWidth, int artHeight) {
+            this.angle = angle;
+            this.bucketKey = bucketKey;
+            this.artPath = artPath;
+            this.frame = frame;
+            this.season = season;
+            this.moonPhase = moonPhase;
+            this.artWidth = artWidth;
+            this.artHeight = artHeight;
+        }
+    }
+
+    public RenderState state() {
+        int[] size = artDimensions();
+        // Same angle that picked the art, with --viewpoint that is not the smoothed orbit angle
+        Integer bucket = degreeArtMap.isEmpty() ? null : bucketKey(degreeArtMap, artPosition(currentDate()));
+        return new RenderState(currentPosition, bucket, currentArtPath, frameCount, season(currentDate()),
+                moonPhase(ZonedDateTime.now(clock).plusDays(dayOffset)), size[0], size[1]);
+    }
+
+    /*************************************************************
+     * Size of the loaded art as {columns, rows} the way a terminal *
+     * draws it, the widest line decides. Wide CJK and emoji take  *
+     * two columns and combining marks none.                       *
+     *************************************************************/
+
+    public int[] artDimensions() {
+        int width = 0;
+        for (String line : artLines) {
+            width = Math.max(width, displayWidth(line));
+        }
+        return new int[]{width, artLines.length};
+    }
+
+    public static int displayWidth(String line) {
+        int width = 0;
+        for (int i = 0; i < line.length(); ) {
+            int codePoint = line.codePointAt(i);
+            int type = Character.getType(codePoint);
+            if (type != Character.NON_SPACING_MARK && type != Character.ENCLOSING_MARK && type != Character.FORMAT) {
+                width += isWideCodePoint(codePoint) ? 2 : 1;
+            }
+            i += Character.charCount(codePoint);
+        }
+        return width;
+    }
+
+    private static boolean isWideCodePoint(int c) {
+        return (c >= 0x1100 && c <= 0x115F) || (c >= 0x2E80 && c <= 0xA4CF && c != 0x303F)
+                || (c >= 0xAC00 && c <= 0xD7A3) || (c >= 0xF900 && c <= 0xFAFF) || (c >= 0xFE30 && c <= 0xFE4F)
+                || (c >= 0xFF00 && c <= 0xFF60) || (c >= 0xFFE0 && c <= 0xFFE6) || (c >= 0x1F300 && c <= 0x1F64F)
+                || (c >= 0x1F900 && c <= 0x1F9FF) || (c >= 0x20000 && c <= 0x3FFFD);
+    }
+
+    /*************************************************************
+     * With --idle, frames come IDLE_SLOWDOWN times further apart  *
+     * once no key has been pressed for that long, saving CPU on   *
+     * an unattended display. Any key brings the full rate back.   *
+     *************************************************************/
+
+    public static long frameInterval(long frameMillis, Instant lastInput, Instant now, Duration idleAfter) {
+        if (idleAfter == null || Duration.between(lastInput, now).compareTo(idleAfter) < 0) {
+            return frameMillis;
+        }
+        return frameMillis * IDLE_SLOWDOWN;
+    }
+
+    /*************************************************************
+     * However short --interval gets, frames never come faster     *
+     * than --max-fps allows so a 0 ms interval can't peg a CPU.   *
+     *************************************************************/
+
+    public static long clampInterval(long requested, long floor) {
+        return Math.max(requested, floor);
+    }
+
+    /*******************************************************************
+     * Non blocking alternative to startTwinkling for programs that     *
+     * already own an executor, one frame is rendered per tick and the  *
+     * returned future cancels the clock.                               *
+     *******************************************************************/
+
+    public ScheduledFuture<?> scheduleTwinkling(ScheduledExecutorService executor) {
+        return executor.scheduleAtFixedRate(this::renderFrame, 0, clampInterval(frameMillis, minFrameMillis),
+                TimeUnit.MILLISECONDS);
+    }
+
+    /*********************************************************************
+     * One tick of the clock, prints the next frame to the terminal.      *
+     *********************************************************************/
+
+    public synchronized void renderFrame() {
+        long now = System.nanoTime();
+        if (lastFrameNanos != 0) {
+            frameDurations.addLast(now - lastFrameNanos);
+            while (frameDurations.size() > fpsWindow) {
+                frameDurations.removeFirst();
+            }
+        }
+        lastFrameNanos = now;
+        List<String> frame = nextFrame();
+        if (highlightFrames > 0) {
+            // Inverted colors while the new bucket settles in
+            writeFrame(frame, CLEAR_SCREEN + (colorEnabled ? "\033[7m" : ""), colorEnabled ? "\033[0m" : "");
+            highlightFrames--;
+        } else {
+            writeFrame(frame, CLEAR_SCREEN, "");
+        }
+        if (bannerFrames > 0) {
+            bannerFrames--;
+        }
+        if (transitionFrames > 0) {
+            transitionFrames--;
+        }
+    }
+
+    /**************************************************************
+     * Static regions keep a foreground planet or label perfectly  *
+     * still while the stars around it carry on twinkling.         *
+     **************************************************************/
+
+    public void addStaticRegion(int top, int left, int bottom, int right) {
+        staticRegions.add(new int[]{Math.min(top, bottom), Math.min(left, right),
+                Math.max(top, bottom), Math.max(left, right)});
+    }
+
+    public boolean isStatic(int row, int col) {
+        for (int[] region : staticRegions) {
+            if (row >= region[0] && row <= region[2] && col >= region[1] && col <= region[3]) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /********************************************************************
+     * Star lifetimes for --lifetime. A share of the art's stars are     *
+     * mortal, each frame they age and the burnt out ones go dark while  *
+     * new stars are born in empty sky. Births make up for deaths so the *
+     * count stays within a tenth of what the art started with.          *
+     ********************************************************************/
+
+    private String[] ageStars() {
+        if (lifetimeArt != originalArt) {
+            lifetimeArt = originalArt;
+            mortalStars.clear();
+            deadStars.clear();
+            initialStarCount = 0;
+            for (int row = 0; row < originalArt.length; row++) {
+                for (int col = 0; col < originalArt[row].length(); col++) {
+                    if (originalArt[row].charAt(col) == '*') {
+                        initialStarCount++;
+                        if (!isStatic(row, col) && random.nextDouble() < MORTAL_FRACTION) {
+                            mortalStars.put(cellKey(row, col), 1 + random.nextInt(starLifetime));
+                        }
+                    }
+                }
+            }
+        }
+
+        int died = 0;
+        for (Iterator<Map.Entry<Integer, Integer>> it = mortalStars.entrySet().iterator(); it.hasNext(); ) {
+            Map.Entry<Integer, Integer> star = it.next();
+            if (star.getValue() <= 1) {
+                it.remove();
+                int row = star.getKey() >> 16;
+                int col = star.getKey() & 0xFFFF;
+                if (originalArt[row].charAt(col) == '*') {
+                    deadStars.add(star.getKey());
+                }
+                died++;
+            } else {
+                star.setValue(star.getValue() - 1);
+            }
+        }
+
+        int band = initialStarCount / 10 + 1;
+        int births = died + random.nextInt(3) - 1;
+        births = Math.max(births, initialStarCount - band - livingStarCount());
+        births = Math.min(births, initialStarCount + band - livingStarCount());
+        for (int i = 0; i < births; i++) {
+            spawnStar();
+        }
+        return skyWithLifetimes();
+    }
+
+    private void spawnStar() {
+        for (int attempt = 0; attempt < 20; attempt++) {
+            int row = random.nextInt(originalArt.length);
+            if (originalArt[row].isEmpty()) {
+                continue;
+            }
+            int col = random.nextInt(originalArt[row].length());
+            Integer key = cellKey(row, col);
+            if (isStatic(row, col)) {
+                continue;
+            }
+            boolean empty = originalArt[row].charAt(col) == ' ' && !mortalStars.containsKey(key);
+            if (empty || deadStars.remove(key)) {
+                mortalStars.put(key, 1 + random.nextInt(starLifetime));
+                return;
+            }
+        }
+    }
+
+    private String[] skyWithLifetimes() {
+        String[] sky = Arrays.copyOf(originalArt, originalArt.length);
+        Set<Integer> rows = new HashSet<>();
+        for (Integer key : deadStars) {
+            rows.add(key >> 16);
+        }
+        for (Integer key : mortalStars.keySet()) {
+            rows.add(key >> 16);
+        }
+        for (Integer row : rows) {
+            char[] cells = sky[row].toCharArray();
+            for (int col = 0; col < cells.length; col++) {
+                Integer key = cellKey(row, col);
+                if (deadStars.contains(key)) {
+                    cells[col] = ' ';
+                } else if (mortalStars.containsKey(key)) {
+                    cells[col] = '*';
+                }
+            }
+            sky[row] = new String(cells);
+        }
+        return sky;
+    }
+
+    // Stars lit in the sky with --lifetime, the art's own minus the burnt out plus the newborn
+    public int livingStarCount() {
+        int spawned = 0;
+        for (Integer key : mortalStars.keySet()) {
+            if (originalArt[key >> 16].charAt(key & 0xFFFF) != '*') {
+                spawned++;
+            }
+        }
+        return initialStarCount - deadStars.size() + spawned;
+    }
+
+    /*************************************************************
+     * --phase-period, every star rides its own brightness wave    *
+     * shifted by the phase it was given when the art loaded, so   *
+     * the field never brightens and dims all at once.             *
+     *************************************************************/
+
+    public static char phaseGlyph(char[] fadeChars, double phase, long frame, int period) {
+        double brightness = 0.5 + 0.5 * Math.cos(2 * Math.PI * ((double) frame / period + phase));
+        return brightnessGlyph(fadeChars, brightness);
+    }
+
+    // Full brightness is the first fade character, going dark the last
+    public static char brightnessGlyph(char[] fadeChars, double brightness) {
+        return fadeChars[Math.min(fadeChars.length - 1, (int) ((1 - brightness) * fadeChars.length))];
+    }
+
+    /*************************************************************
+     * --shimmer, like --phase-period but each star also gets its  *
+     * own frequency, between SHIMMER_MIN_HZ and SHIMMER_MAX_HZ,   *
+     * and the wave runs on the wall clock rather than the frame   *
+     * count, so --interval, --max-fps and --idle leave it alone.  *
+     *************************************************************/
+
+    public static double shimmerBrightness(double seconds, double frequency, double phase) {
+        return (Math.sin(seconds * frequency + phase) + 1) / 2;
+    }
+
+    /*************************************************************
+     * --wind DX,DY adds WIND_WAVE radians of phase per cell along *
+     * the direction to a star's brightness wave, so the shimmer   *
+     * of --phase-period or --shimmer sweeps across the field with *
+     * the breeze. The direction is scaled to length 1.            *
+     *************************************************************/
+
+    public static double windPhase(int row, int col, double dx, double dy) {
+        double length = Math.hypot(dx, dy);
+        if (length == 0) {
+            return 0;
+        }
+        // Behind in phase further downwind, so the crests move along (dx, dy)
+        return -WIND_WAVE * (dx * col + dy * row) / length;
+    }
+
+    /*************************************************************
+     * Heat trail for --trail. A star that twinkles walks down its *
+     * fade table one step per frame for trailLength frames, then  *
+     * shows steady again until it is picked the next time.        *
+     *************************************************************/
+
+    private char trailStep(int row, int col, char[] fadeChars) {
+        Integer key = cellKey(row, col);
+        Integer age = trails.get(key);
+        if (age == null) {
+            if (random.nextFloat() >= twinkleChance) {
+                return fadeChars[0];
+            }
+            age = 0;
+        }
+        if (age >= trailLength) {
+            trails.remove(key);
+            return fadeChars[0];
+        }
+        trails.put(key, age + 1);
+        return trailGlyph(fadeChars, age);
+    }
+
+    public static char trailGlyph(char[] fadeChars, int age) {
+        return fadeChars[Math.min(age, fadeChars.length - 1)];
+    }
+
+    private static Integer cellKey(int row, int col) {
+        return (row << 16) | col;
+    }
+
+    /*****************************************************************
+     * Low-pass on the displayed angle for --smooth. Each frame the    *
+     * shown angle closes the given fraction of the gap to the real    *
+     * one, going the short way around the orbit.                      *
+     *****************************************************************/
+
+    public static double smoothAngle(double shown, double raw, double factor) {
+        double gap = ((raw - shown) % FULL_ORBIT + FULL_ORBIT * 1.5) % FULL_ORBIT - FULL_ORBIT / 2.0;
+        return ((shown + gap * factor) % FULL_ORBIT + FULL_ORBIT) % FULL_ORBIT;
+    }
+
+    private double displayAngle(double raw) {
+        smoothedAngle = Double.isNaN(smoothedAngle) ? raw : smoothAngle(smoothedAngle, raw, angleSmoothing);
+        return smoothedAngle;
+    }
+
+    /****************************************************************
+     * Status bar under the art. Each enabled segment is rendered and *
+     * joined with its neighbours in the same slot, then the left,    *
+     * center and right slots are laid out across the art's width.    *
+     ****************************************************************/
+
+    private String statusLine(LocalDate date, double position) {
+        ZonedDateTime now = ZonedDateTime.now(clock).plusDays(dayOffset);
+        Map<StatusSegment, String> values = new EnumMap<>(StatusSegment.class);
+        values.put(StatusSegment.ANGLE, String.format("Angle: %.2f°", position));
+        values.put(StatusSegment.DATE, "Date: " + date);
+        values.put(StatusSegment.TIME, "Time: " + LocalTime.now(clock).format(MEDIUM_CLOCKED_IN));
+        values.put(StatusSegment.JULIAN, String.format("JD: %.5f", julianDate(now)));
+        values.put(StatusSegment.DAY, "Day: " + dayOfYear(date));
+        values.put(StatusSegment.FPS, String.format("FPS: %.1f", averageFps(frameDurations)));
+        String[] cells = statusCells(statusSegments, values);
+        return layoutStatus(cells, canvasWidth > 0 ? canvasWidth : artWidth());
+    }
+
+    public static String[] statusCells(Map<StatusSegment, Alignment> segments, Map<StatusSegment, String> values) {
+        String[] cells = {"", "", ""};
+        for (Map.Entry<StatusSegment, Alignment> segment : segments.entrySet()) {
+            int slot = segment.getValue().ordinal();
+            cells[slot] = cells[slot].isEmpty() ? values.get(segment.getKey())
+                    : cells[slot] + "  " + values.get(segment.getKey());
+        }
+        return cells;
+    }
+
+    public static String layoutStatus(String[] cells, int width) {
+        StringBuilder line = new StringBuilder(cells[0]);
+        int centerStart = Math.max(line.length() + 1, (width - cells[1].length()) / 2);
+        if (!cells[1].isEmpty()) {
+            line.append(" ".repeat(centerStart - line.length())).append(cells[1]);
+        }
+        if (!cells[2].isEmpty()) {
+            int rightStart = Math.max(line.length() + 1, width - cells[2].length());
+            line.append(" ".repeat(rightStart - line.length())).append(cells[2]);
+        }
+        return line.toString();
+    }
+
+    // Stars that can twinkle in the loaded art, for checking the density of generated skies
+    public int starCount() {
+        int stars = 0;
+        if (artLines == null) {
+            return stars;
+        }
+        for (String line : artLines) {
+            for (char c : line.toCharArray()) {
+                if (isStarSource(c)) {
+                    stars++;
+                }
+            }
+        }
+        return stars;
+    }
+
+    private int artWidth() {
+        int width = 0;
+        for (String line : artLines) {
+            width = Math.max(width, line.length());
+        }
+        return width;
+    }
+
+    /*************************************************************
+     * The FPS segment averages the gaps between the last           *
+     * --fps-window frames so the readout holds still instead of    *
+     * jumping with every late frame. 0 until two frames are drawn. *
+     *************************************************************/
+
+    public static double averageFps(Collection<Long> frameNanos) {
+        long total = 0;
+        for (long nanos : frameNanos) {
+            total += nanos;
+        }
+        return total > 0 ? frameNanos.size() * 1e9 / total : 0;
+    }
+
+    private void parseStatusSegments(String value) {
+        statusSegments.clear();
+        for (String part : value.split(",")) {
+            String[] segment = part.split(":");
+            Alignment slot = segment.length > 1 ? Alignment.valueOf(segment[1].trim().toUpperCase()) : Alignment.LEFT;
+            statusSegments.put(StatusSegment.valueOf(segment[0].trim().toUpperCase()), slot);
+        }
+    }
+
+    /******************************************************************
+     * Endless supply of frames for embedding, each call to next()     *
+     * twinkles a fresh frame without printing or sleeping.            *
+     ******************************************************************/
+
+    public Iterator<List<String>> frames() {
+        return new Iterator<>() {
+            @Override
+            public boolean hasNext() {
+                return true;
+            }
+
+            @Override
+            public List<String> next() {
+                return nextFrame();
+            }
+        };
+    }
+
+    /*********************************************************************
+     * Twinkles the next frame and lays it out on the canvas. The         *
+     * pristine art is restored afterwards, ready for the frame after.    *
+     *********************************************************************/
+
+    public synchronized List<String> nextFrame() {
+        double position = advanceFrame();
+        List<String> frame = layoutFrame();
+        if (sidePanel) {
+            List<String> panel = boxPanel(panelLines(currentDate(), position));
+            if (asciiOnly) {
+                panel.replaceAll(Spacerace::toAscii);
+            }
+            frame = sideBySide(frame, panel, PANEL_GAP);
+        }
+        if (!statusSegments.isEmpty()) {
+            frame.add(statusLine(currentDate(), shownAngle(position, artPosition(currentDate()), degreeArtMap,
+                    bucketAngle)));
+        }
+        artLines = Arrays.copyOf(originalArt, originalArt.length);
+        return frame;
+    }
+
+    /****************************************************************
+     * --layout side, the art on the left and a boxed panel of the   *
+     * readouts to its right. The art column is as wide as its       *
+     * widest line so the panel never overlaps it.                   *
+     ****************************************************************/
+
+    private List<String> panelLines(LocalDate date, double position) {
+        LocalDate nextEvent = nextSeasonEvent(date);
+        List<String> lines = new ArrayList<>();
+        lines.add(String.format("Angle   %.2f°", position));
+        lines.add("Date    " + date);
+        lines.add("Season  " + season(date));
+        lines.add(String.format("Moon    %.0f%% through the cycle", moonPhase(ZonedDateTime.now(clock).plusDays(dayOffset)) * 100));
+        lines.add("Next    " + nextEvent + " (" + ChronoUnit.DAYS.between(date, nextEvent) + " days)");
+        return lines;
+    }
+
+    public static List<String> boxPanel(List<String> lines) {
+        int width = 0;
+        for (String line : lines) {
+            width = Math.max(width, line.length());
+        }
+        List<String> box = new ArrayList<>();
+        box.add("┌" + "─".repeat(width + 2) + "┐");
+        for (String line : lines) {
+            box.add("│ " + line + " ".repeat(width - line.length()) + " │");
+        }
+        box.add("└" + "─".repeat(width + 2) + "┘");
+        return box;
+    }
+
+    public static List<String> sideBySide(List<String> left, List<String> right, int gap) {
+        int width = 0;
+        for (String line : left) {
+            width = Math.max(width, visibleLength(line));
+        }
+        List<String> combined = new ArrayList<>();
+        for (int i = 0; i < Math.max(left.size(), right.size()); i++) {
+            String line = i < left.size() ? left.get(i) : "";
+            if (i < right.size()) {
+                line += " ".repeat(width - visibleLength(line) + gap) + right.get(i);
+            }
+            combined.add(line);
+        }
+        return combined;
+    }
+
+    // Columns a line takes up on screen, color escapes take none
+    public static int visibleLength(String line) {
+        return line.replaceAll("\033\\[[0-9;]*m", "").length();
+    }
+
+    /******************************************************************
+     * The next frame as lines of colored spans rather than strings    *
+     * with escapes, for programs drawing the clock with their own     *
+     * toolkit. A null color means the toolkit's default.              *
+     ******************************************************************/
+
+    public synchronized List<List<Span>> styledText() {
+        advanceFrame();
+        List<List<Span>> text = new ArrayList<>();
+        for (int row = 0; row < artLines.length; row++) {
+            List<Span> spans = new ArrayList<>();
+            String line = artLines[row];
+            int start = 0;
+            for (int col = 1; col <= line.length(); col++) {
+                if (col == line.length() || !Arrays.equals(cellColor(row, col, line.charAt(col)),
+                        cellColor(row, start, line.charAt(start)))) {
+                    spans.add(new Span(line.substring(start, col), cellColor(row, start, line.charAt(start))));
+                    start = col;
+                }
+            }
+            text.add(spans);
+        }
+        artLines = Arrays.copyOf(originalArt, originalArt.length);
+        return text;
+    }
+
+    public static final class Span {
+        public final String text;
+        public final int[] color;
+
+        public Span(String text, int[] color) {
+            this.text = text;
+            this.color = color;
+        }
+    }
+
+    /*******************************************************************
+     * Twinkles the stars, stamps the coordinates, date and time onto   *
+     * their markers and draws the overlays into artLines. Callers put  *
+     * the pristine art back once they have taken what they need.       *
+     *******************************************************************/
+
+    private double advanceFrame() {
+        if (originalArt == null) {
+            originalArt = Arrays.copyOf(artLines, artLines.length);
+        }
+        LocalDate date = currentDate();
+        checkBucketCrossing(date);
+        prefetchNextBucket(date);
+        checkSeasonEvent(date);
+        // Art follows the precise angle, everything shown on screen uses the smoothed one
+        double position = displayAngle(scenePosition(date));
+        currentPosition = position;
+        String formattedDate = date.format(DateTimeFormatter.ofPattern("yyyy-MM-dd"));
+        skyArt = starLifetime > 0 ? ageStars() : originalArt;
+        twinkleChance = moonPulse ? TWINKLE_CHANCE * (1.5 - moonBrightness(ZonedDateTime.now(clock).plusDays(dayOffset)))
+                : TWINKLE_CHANCE;
+        twinkleChance = Math.min(1, twinkleChance / brightness);
+        Constellation today = constellations != null ? constellationOfTheDay(constellations, currentArtPath, date) : null;
+        double constellationChance = twinkleChance / constellationPulse(frameCount);
+        artLines = Arrays.copyOf(skyArt, skyArt.length);
+        if (shimmer && shimmerStartMillis < 0) {
+            shimmerStartMillis = clock.millis();
+        }
+        double shimmerSeconds = (clock.millis() - shimmerStartMillis) / 1000.0;
+        for (int j = 0; j < artLines.length; j++) {
+            char[] lineChars = artLines[j].toCharArray();
+            for (int k = 0; k < lineChars.length; k++) {
+                if (isStatic(j, k)) {
+                    continue;
+                }
+                if (shimmer && (skyArt[j].charAt(k) == '*' || skyArt[j].charAt(k) == plusStar)) {
+                    char[] table = skyArt[j].charAt(k) == '*' ? starFadeChars : plusFadeChars;
+                    double phase = 2 * Math.PI * starPhases[j][k] + windPhase(j, k, windX, windY);
+                    lineChars[k] = brightnessGlyph(table, shimmerBrightness(shimmerSeconds, starFrequencies[j][k], phase));
+                    continue;
+                }
+                if (phasePeriod > 0 && (skyArt[j].charAt(k) == '*' || skyArt[j].charAt(k) == plusStar)) {
+                    char[] table = skyArt[j].charAt(k) == '*' ? starFadeChars : plusFadeChars;
+                    double phase = starPhases[j][k] + windPhase(j, k, windX, windY) / (2 * Math.PI);
+                    lineChars[k] = phaseGlyph(table, phase, frameCount, phasePeriod);
+                    continue;
+                }
+                if (trailLength > 0 && (skyArt[j].charAt(k) == '*' || skyArt[j].charAt(k) == plusStar)) {
+                    lineChars[k] = trailStep(j, k, skyArt[j].charAt(k) == '*' ? starFadeChars : plusFadeChars);
+                    continue;
+                }
+                double chance = today != null && today.contains(j, k) ? constellationChance : twinkleChance;
+                chance = Math.min(1, chance * rowIntensity(j, artLines.length, gradientTop, gradientBottom));
+                if (skyArt[j].charAt(k) == '*' && random.nextFloat() < chance) {
+                    int fadeIndex = pickWeighted(starFadeWeights, random);
+                    lineChars[k] = starFadeChars[fadeIndex];
+                }
+                else if (skyArt[j].charAt(k) == plusStar && random.nextFloat() < chance) {
+                    int fadeIndex = pickWeighted(plusFadeWeights, random);
+                    lineChars[k] = plusFadeChars[fadeIndex];
+                }
+            }
+            artLines[j] = new String(lineChars);
+        }
+        LocalTime updatedTime = LocalTime.now(clock);
+        String updatedFormattedTime = updatedTime.truncatedTo(ChronoUnit.MINUTES).format(MEDIUM_CLOCKED_IN);
+        OVER_WRITE("&", String.valueOf(shownAngle(position, artPosition(date), degreeArtMap, bucketAngle)), "=",
+                formattedDate, "#", updatedFormattedTime);
+        if (showIss) {
+            overlayIss();
+        }
+        if (showSun) {
+            overlaySun(position);
+        }
+        if (showTerminator) {
+            overlayTerminator(position);
+        }
+        if (showOrbitDial) {
+            overlayOrbitDial(position);
+        }
+        if (showLogo) {
+            overlayLogo();
+        }
+        logCsv(date, position);
+        marqueeOffset += marqueeSpeed;
+        frameCount++;
+        return position;
+    }
+
+
+    /****************************************************************
+     * Keys are read on their own thread with the terminal switched   *
+     * out of line mode so a single press arrives without Enter.      *
+     * Only runs with a real terminal, piped input is left alone.     *
+     ****************************************************************/
+
+    public void startKeyListener() {
+        if (System.console() == null) {
+            return;
+        }
+        try {
+            savedTerminalState = stty("-g").trim();
+            stty("-icanon -echo min 1");
+        } catch (IOException | InterruptedException e) {
+            return; // No stty, keys stay unavailable
+        }
+        Runtime.getRuntime().addShutdownHook(new Thread(this::restoreTerminal));
+        Thread keyThread = new Thread(() -> {
+            try {
+                String key;
+                while ((key = readKey()) != null) {
+                    lastInput = clock.instant();
+                    handleKey(key);
+                }
+            } catch (IOException e) {
+                System.err.println("Error reading keys: " + e.getMessage());
+            }
+        });
+        keyThread.setDaemon(true);
+        keyThread.start();
+    }
+
+    private static String stty(String settings) throws IOException, InterruptedException {
+        Process process = new ProcessBuilder("sh", "-c", "stty " + settings + " < /dev/tty").start();
+        String output = new String(process.getInputStream().readAllBytes());
+        process.waitFor();
+        return output;
+    }
+
+    /*************************************************************
+     * Terminal size as {columns, rows}. Pipes and CI have no tty  *
+     * to ask, they get 80x24 and a warning instead of a crash.    *
+     *************************************************************/
+
+    private static int[] terminalSize() {
+        String size = null;
+        try {
+            size = stty("size");
+        } catch (IOException | InterruptedException e) {
+            // Falls through to the default below
+        }
+        int[] resolved = resolveTerminalSize(size);
+        if (resolved == DEFAULT_TERMINAL_SIZE) {
+            System.err.println("Couldn't read the terminal size, using 80x24");
+        }
+        return resolved;
+    }
+
+    public static int[] resolveTerminalSize(String sttySize) {
+        if (sttySize == null) {
+            return DEFAULT_TERMINAL_SIZE;
+        }
+        String[] parts = sttySize.trim().split("\\s+");
+        try {
+            int rows = Integer.parseInt(parts[0]);
+            int columns = Integer.parseInt(parts[1]);
+            if (rows > 0 && columns > 0) {
+                return new int[]{columns, rows};
+            }
+        } catch (NumberFormatException | ArrayIndexOutOfBoundsException e) {
+            // stty printed something other than "rows columns"
+        }
+        return DEFAULT_TERMINAL_SIZE;
+    }
+
+    private void restoreTerminal() {
+        try {
+            if (savedTerminalState != null) {
+                stty(savedTerminalState);
+            }
+        } catch (IOException | InterruptedException e) {
+            // Nothing left to do on the way out
+        }
+    }
+
+    /*********************************************************
+     * Returns a single character, or the body of an escape   *
+     * sequence such as "[5~" for PageUp. Null at end of input. *
+     *********************************************************/
+
+    private static String readKey() throws IOException {
+        int c = System.in.read();
+        if (c == -1) {
+            return null;
+        }
+        if (c != 27) {
+            return String.valueOf((char) c);
+        }
+        StringBuilder sequence = new StringBuilder();
+        int next = System.in.read();
+        if (next != '[' && next != 'O') {
+            return "\033";
+        }
+        sequence.append((char) next);
+        while ((next = System.in.read()) != -1) {
+            sequence.append((char) next);
+            if (Character.isLetter(next) || next == '~') {
+                break;
+            }
+        }
+        return sequence.toString();
+    }
+
+    public void handleKey(String key) {
+        Action action = keyBindings.get(key);
+        logEvent(key, action);
+        if (action == null) {
+            return;
+        }
+        switch (action) {
+            case BACK_WEEK: dayOffset = shiftOffset(LocalDate.now(clock), dayOffset, false, false); break;
+            case FORWARD_WEEK: dayOffset = shiftOffset(LocalDate.now(clock), dayOffset, true, false); break;
+            case BACK_MONTH: dayOffset = shiftOffset(LocalDate.now(clock), dayOffset, false, true); break;
+            case FORWARD_MONTH: dayOffset = shiftOffset(LocalDate.now(clock), dayOffset, true, true); break;
+            case TODAY: dayOffset = 0; break;
+            case LOGO:
+                showLogo = !showLogo;
+                break;
+            case THEME:
+                startColorTransition(palette);
+                palette = nextTheme(themes, palette);
+                showBanner("Theme: " + palette.name().toLowerCase());
+                break;
+            case BRIGHTER:
+                brightness = clampBrightness(brightness + BRIGHTNESS_STEP);
+                showBanner(String.format("Brightness: %.1fx", brightness));
+                break;
+            case DIMMER:
+                brightness = clampBrightness(brightness - BRIGHTNESS_STEP);
+                showBanner(String.format("Brightness: %.1fx", brightness));
+                break;
+            case BUCKET_ANGLE:
+                bucketAngle = !bucketAngle;
+                showBanner(bucketAngle ? "Angle: bucket" : "Angle: precise");
+                break;
+            case PAUSE:
+                paused = !paused;
+                showBanner(paused ? "Paused" : "Running");
+                break;
+            case QUIT:
+                System.exit(EXIT_OK); // The shutdown hook puts the terminal back
+                return;
+            default: return;
+        }
+        renderFrame();
+    }
+
+    /****************************************************************
+     * Key bindings, key sequence (as readKey returns it) to action. *
+     * --keys FILE takes "action key" lines, an action listed there  *
+     * loses its default keys so rebinding quit to x frees up q.     *
+     * Keys are single characters or one of the names in KEY_NAMES.  *
+     ****************************************************************/
+
+    public enum Action {
+        BACK_WEEK, FORWARD_WEEK, BACK_MONTH, FORWARD_MONTH, TODAY, LOGO, THEME, BRIGHTER, DIMMER, BUCKET_ANGLE, PAUSE, QUIT
+    }
+
+    private static final Map<String, List<String>> KEY_NAMES = Map.of(
+            "pageup", List.of("[5~"),
+            "pagedown", List.of("[6~"),
+            "shift-pageup", List.of("[5;2~"),
+            "shift-pagedown", List.of("[6;2~"),
+            "home", List.of("[H", "[1~", "[7~", "OH"),
+            "space", List.of(" "));
+
+    public static Map<String, Action> defaultKeyBindings() {
+        Map<String, Action> bindings = new HashMap<>();
+        bindKey(bindings, "pageup", Action.BACK_WEEK);
+        bindKey(bindings, "pagedown", Action.FORWARD_WEEK);
+        bindKey(bindings, "shift-pageup", Action.BACK_MONTH);
+        bindKey(bindings, "shift-pagedown", Action.FORWARD_MONTH);
+        bindKey(bindings, "home", Action.TODAY);
+        bindKey(bindings, "d", Action.LOGO);
+        bindKey(bindings, "c", Action.THEME);
+        bindKey(bindings, "+", Action.BRIGHTER);
+        bindKey(bindings, "=", Action.BRIGHTER);
+        bindKey(bindings, "-", Action.DIMMER);
+        bindKey(bindings, "b", Action.BUCKET_ANGLE);
+        bindKey(bindings, "p", Action.PAUSE);
+        bindKey(bindings, "q", Action.QUIT);
+        return bindings;
+    }
+
+    private static void bindKey(Map<String, Action> bindings, String key, Action action) {
+        List<String> sequences = KEY_NAMES.get(key.toLowerCase());
+        if (sequences == null && key.length() != 1) {
+            throw new IllegalArgumentException("Unknown key " + key + ", expected a single character or one of "
+                    + new TreeSet<>(KEY_NAMES.keySet()));
+        }
+        for (String sequence : sequences != null ? sequences : List.of(key)) {
+            bindings.put(sequence, action);
+        }
+    }
+
+    public static void loadKeyBindings(Map<String, Action> bindings, List<String> lines) {
+        Set<Action> rebound = new HashSet<>();
+        for (String line : lines) {
+            String trimmed = line.trim();
+            if (trimmed.isEmpty() || trimmed.startsWith("#")) {
+                continue;
+            }
+            String[] fields = trimmed.split("\\s+");
+            if (fields.length != 2) {
+                throw new IllegalArgumentException("Expected action key: " + line);
+            }
+            Action action = Action.valueOf(fields[0].toUpperCase().replace('-', '_'));
+            if (rebound.add(action)) {
+                bindings.values().removeIf(bound -> bound == action);
+            }
+            bindKey(bindings, fields[1], action);
+        }
+    }
+
+    /*****************************************************************
+     * Time machine step, a week per press or a calendar month with   *
+     * Shift held. PageDown travels forward and PageUp back in time.   *
+     *****************************************************************/
+
+    public static long shiftOffset(LocalDate today, long offset, boolean forward, boolean month) {
+        LocalDate virtual = today.plusDays(offset);
+        if (month) {
+            virtual = forward ? virtual.plusMonths(1) : virtual.minusMonths(1);
+        } else {
+            virtual = forward ? virtual.plusWeeks(1) : virtual.minusWeeks(1);
+        }
+        return ChronoUnit.DAYS.between(today, virtual);
+    }
+
+    // A line above the frame for HIGHLIGHT_FRAMES frames, so a key press shows what it changed
+    private void showBanner(String text) {
+        banner = text;
+        bannerFrames = HIGHLIGHT_FRAMES;
+    }
+
+    /*************************************************************
+     * Star brightness from the + and - keys. Brighter stars fade  *
+     * less often, the twinkle odds are divided by the multiplier. *
+     *************************************************************/
+
+    public static double clampBrightness(double value) {
+        return Math.max(MIN_BRIGHTNESS, Math.min(MAX_BRIGHTNESS, value));
+    }
+
+    /*************************************************************
+     * Next theme in the cycle, wrapping from the last one back to *
+     * the first. A palette outside the list starts it over.       *
+     *************************************************************/
+
+    public static Palette nextTheme(List<Palette> themes, Palette current) {
+        int index = themes.indexOf(current);
+        return themes.get((index + 1) % themes.size());
+    }
+
+    public void setClock(Clock clock) {
+        this.clock = clock;
+    }
+
+    // Directory the mapped art is read from, --art-root or the test fixtures
+    public void setArtRoot(String artRoot) {
+        this.artRoot = artRoot;
+    }
+
+    // Fixed seed for reproducible twinkling, --seed or tests
+    public void setSeed(long seed) {
+        random = new Random(seed);
+    }
+
+    // Same as SPACERACE_ANGLE, the value goes through the same parsing so junk unpins
+    public void setPinnedAngle(String value) {
+        pinnedAngle = parseAngle(value);
+    }
+
+    // The body whose orbit picks the art, --viewpoint or null for the one being followed
+    public void setViewpoint(Body viewpoint) {
+        this.viewpoint = viewpoint;
+    }
+
+    // Holds every render that follows at one instant, for programs embedding the display
+    public void setInstant(ZonedDateTime instant) {
+        setClock(Clock.fixed(instant.toInstant(), instant.getZone()));
+    }
+
+    public void clearInstant() {
+        setClock(Clock.systemDefaultZone());
+    }
+
+    public LocalDate currentDate() {
+        return LocalDate.now(clock).plusDays(dayOffset);
+    }
+
+    /*************************************************************
+     * Weighted pick of a fade index, a weight of 60 next to 5s   *
+     * means that character shows up far more often.              *
+     *************************************************************/
+
+    public static int pickWeighted(int[] weights, Random random) {
+        int total = 0;
+        for (int weight : weights) {
+            total += weight;
+        }
+        int roll = random.nextInt(total);
+        for (int i = 0; i < weights.length; i++) {
+            roll -= weights[i];
+            if (roll < 0) {
+                return i;
+            }
+        }
+        return weights.length - 1;
+    }
+
+    /************************************************************
+     * Swaps the blank in a star's fade table for another "off"  *
+     * character so dense fields dim instead of showing holes.   *
+     ************************************************************/
+
+    public void setOffChar(char glyph, char off) {
+        char[] table = glyph == '┼' ? plusFadeChars : glyph == '*' ? starFadeChars : null;
+        if (table == null) {
+            throw new IllegalArgumentException("No twinkle table for '" + glyph + "'");
+        }
+        for (int i = 0; i < table.length; i++) {
+            if (table[i] == ' ') {
+                table[i] = off;
+            }
+        }
+    }
+
+    private void parseOffChars(String value) {
+        if (value.codePointCount(0, value.length()) == 1) {
+            setOffChar('*', value.charAt(0));
+            setOffChar('┼', value.charAt(0));
+            return;
+        }
+        for (String pair : value.split(",")) {
+            if (pair.length() != 3 || pair.charAt(1) != '=') {
+                throw new IllegalArgumentException("Expected GLYPH=CHAR but got: " + pair);
+            }
+            setOffChar(pair.charAt(0), pair.charAt(2));
+        }
+    }
+
+    private static int parsePositive(String flag, String value) {
+        int parsed = Integer.parseInt(value);
+        if (parsed <= 0) {
+            throw new IllegalArgumentException(flag + " must be a positive number: " + value);
+        }
+        return parsed;
+    }
+
+    private static int[] parseWeights(String value, int expected) {
+        String[] parts = value.split(",");
+        if (parts.length != expected) {
+            throw new IllegalArgumentException("Expected " + expected + " weights but got " + parts.length + ": " + value);
+        }
+        int[] weights = new int[expected];
+        int total = 0;
+        for (int i = 0; i < expected; i++) {
+            weights[i] = Integer.parseInt(parts[i].trim());
+            if (weights[i] < 0) {
+                throw new IllegalArgumentException("Weights must not be negative: " + value);
+            }
+            total += weights[i];
+        }
+        if (total == 0) {
+            throw new IllegalArgumentException("At least one weight must be positive: " + value);
+        }
+        return weights;
+    }
+
+    /***************************************************************
+     * Moves the ISS blip along its row, wrapping back to the left  *
+     * edge. Drawn on the frame copy so the pristine art is safe.   *
+     ***************************************************************/
+
+    public int advanceIss(int lineLength) {
+        if (lineLength <= 0) {
+            return issColumn = 0;
+        }
+        issColumn = (issColumn + issSpeed) % lineLength;
+        return issColumn;
+    }
+
+    /*************************************************************
+     * One axis of the logo's motion. A step that would leave      *
+     * 0..limit turns the velocity around instead.                 *
+     *************************************************************/
+
+    public static int[] bounce(int position, int velocity, int limit) {
+        int next = position + velocity;
+        if (next < 0 || next > limit) {
+            velocity = -velocity;
+            next = position + velocity;
+        }
+        return new int[]{Math.max(0, Math.min(limit, next)), velocity};
+    }
+
+    private void overlayLogo() {
+        int width = 0;
+        for (String line : artLines) {
+            width = Math.max(width, line.length());
+        }
+        int[] row = bounce(logoRow, logoRowVelocity, Math.max(0, artLines.length - 1));
+        int[] column = bounce(logoColumn, logoColumnVelocity, Math.max(0, width - LOGO.length()));
+        // Both walls in the same step is the corner everyone waits for
+        if (row[1] != logoRowVelocity && column[1] != logoColumnVelocity) {
+            logoFlashFrames = HIGHLIGHT_FRAMES;
+        } else if (logoFlashFrames > 0) {
+            logoFlashFrames--;
+        }
+        logoRow = row[0];
+        logoRowVelocity = row[1];
+        logoColumn = column[0];
+        logoColumnVelocity = column[1];
+        if (logoRow < artLines.length) {
+            StringBuilder line = new StringBuilder(artLines[logoRow]);
+            while (line.length() < logoColumn + LOGO.length()) {
+                line.append(' ');
+            }
+            line.replace(logoColumn, logoColumn + LOGO.length(), LOGO);
+            artLines[logoRow] = line.toString();
+        }
+    }
+
+    private boolean isLogoCell(int row, int column) {
+        return showLogo && row == logoRow && column >= logoColumn && column < logoColumn + LOGO.length();
+    }
+
+    private void overlayIss() {
+        if (issRow < 0 || issRow >= artLines.length) {
+            return;
+        }
+        int column = advanceIss(artLines[issRow].length());
+        if (artLines[issRow].length() > 0 && artLines[issRow].charAt(column) == ' ') {
+            StringBuilder row = new StringBuilder(artLines[issRow]);
+            row.setCharAt(column, '▪');
+            artLines[issRow] = row.toString();
+        }
+    }
+
+    /****************************************************************
+     * Seen from earth the sun sits half an orbit away, that angle   *
+     * is spread across the width of the art and wraps at the edge.  *
+     ****************************************************************/
+
+    public static int sunColumn(double angle, int width) {
+        if (width <= 0) {
+            return 0;
+        }
+        double sunAngle = (((angle + 180) % FULL_ORBIT) + FULL_ORBIT) % FULL_ORBIT;
+        return (int) Math.round(sunAngle / FULL_ORBIT * width) % width;
+    }
+
+    /****************************************************************
+     * --terminator, a little map of the Earth in the top right      *
+     * corner centered on our own longitude, lit where the sun is    *
+     * up. The sun sits over longitude (12 - hour) * 15 relative to  *
+     * us and the orbit angle sets its declination, so the divide    *
+     * leans with the seasons.                                       *
+     ****************************************************************/
+
+    public static double subsolarLongitude(double hour) {
+        return (12 - hour) * 15;
+    }
+
+    // Degrees of longitude either side of the sun that are in daylight at this latitude
+    public static double daylightHalfWidth(double latitude, double angle) {
+        double declination = Math.toRadians(AXIAL_TILT * Math.sin(Math.toRadians(angle - VERNAL_EQUINOX_ANGLE)));
+        double cosine = -Math.tan(Math.toRadians(latitude)) * Math.tan(declination);
+        return Math.toDegrees(Math.acos(Math.max(-1, Math.min(1, cosine))));
+    }
+
+    public static boolean isSunlit(double longitude, double latitude, double hour, double angle) {
+        double fromSun = ((longitude - subsolarLongitude(hour)) % FULL_ORBIT + FULL_ORBIT + 180) % FULL_ORBIT - 180;
+        return Math.abs(fromSun) <= daylightHalfWidth(latitude, angle);
+    }
+
+    private void overlayTerminator(double position) {
+        LocalTime time = LocalTime.now(clock);
+        double hour = time.getHour() + time.getMinute() / 60.0;
+        for (int r = 0; r < TERMINATOR_ROWS && r + 1 < artLines.length; r++) {
+            double latitude = 60 - r * 120.0 / (TERMINATOR_ROWS - 1);
+            StringBuilder glyph = new StringBuilder();
+            for (int c = 0; c < TERMINATOR_COLUMNS; c++) {
+                double longitude = -180 + (c + 0.5) * FULL_ORBIT / TERMINATOR_COLUMNS;
+                glyph.append(isSunlit(longitude, latitude, hour, position) ? '█' : '░');
+            }
+            String cells = asciiOnly ? toAscii(glyph.toString()) : glyph.toString();
+            String line = artLines[r + 1];
+            int start = line.length() - TERMINATOR_COLUMNS - 2;
+            if (start >= 0) {
+                artLines[r + 1] = line.substring(0, start) + cells + line.substring(start + TERMINATOR_COLUMNS);
+            }
+        }
+    }
+
+    /****************************************************************
+     * --orbit-dial, a little clock face of the orbit in the bottom  *
+     * left corner, clear of the text panel. 0 degrees is at the top *
+     * and the angle grows clockwise, ● marks the earth and ☉ the    *
+     * sun. Columns are doubled so the ring comes out round.         *
+     ****************************************************************/
+
+    public static String[] orbitDial(double angle) {
+        int rows = 2 * DIAL_RADIUS + 1;
+        int columns = 4 * DIAL_RADIUS + 1;
+        char[][] grid = new char[rows][columns];
+        for (char[] row : grid) {
+            Arrays.fill(row, ' ');
+        }
+        for (int degrees = 0; degrees < FULL_ORBIT; degrees += 10) {
+            int[] cell = dialCell(degrees);
+            grid[cell[0]][cell[1]] = '·';
+        }
+        grid[DIAL_RADIUS][2 * DIAL_RADIUS] = '☉';
+        int[] earth = dialCell(angle);
+        grid[earth[0]][earth[1]] = '●';
+        String[] dial = new String[rows + 2];
+        dial[0] = "┌" + "─".repeat(columns) + "┐";
+        for (int r = 0; r < rows; r++) {
+            dial[r + 1] = "│" + new String(grid[r]) + "│";
+        }
+        dial[rows + 1] = "└" + "─".repeat(columns) + "┘";
+        return dial;
+    }
+
+    // {row, column} of an angle on the dial's ring, inside the border
+    public static int[] dialCell(double angle) {
+        double radians = Math.toRadians(angle);
+        return new int[]{DIAL_RADIUS - (int) Math.round(DIAL_RADIUS * Math.cos(radians)),
+                2 * DIAL_RADIUS + (int) Math.round(2 * DIAL_RADIUS * Math.sin(radians))};
+    }
+
+    private void overlayOrbitDial(double position) {
+        String[] dial = orbitDial(position);
+        int top = artLines.length - dial.length - 1;
+        for (int r = 0; r < dial.length && top >= 0; r++) {
+            String cells = asciiOnly ? toAscii(dial[r]).replace('·', '.').replace('●', 'o').replace('☉', 'O') : dial[r];
+            String line = artLines[top + r];
+            int start = 2;
+            if (line.length() >= start + cells.length()) {
+                artLines[top + r] = line.substring(0, start) + cells + line.substring(start + cells.length());
+            }
+        }
+    }
+
+    private void overlaySun(double position) {
+        int row = artLines.length / 2;
+        if (row >= artLines.length || artLines[row].isEmpty()) {
+            return;
+        }
+        int column = sunColumn(position, artLines[row].length());
+        if (artLines[row].charAt(column) == ' ') {
+            StringBuilder line = new StringBuilder(artLines[row]);
+            line.setCharAt(column, '☀');
+            artLines[row] = line.toString();
+        }
+    }
+
+    /***************************************************************
+     * Clears the previous string array output to help with format. *
+     ***************************************************************/
+
+
+    private void clearScreen() {
+        // Clear the console - this method is platform-dependent.
+        // For Unix/Linux/Mac:
+        System.out.print(CLEAR_SCREEN);
+        System.out.flush();
+    }
+
+    /******************************************************************************
+     * Finds the positions of two specified markers in the array. Use this to find *
+     * and mark locations in the array for desired changes.                        *
+     ******************************************************************************/
+    public void findMarkerPositions(char marker1, char marker2) {
+        for (int i = 0; i < artLines.length; i++) {
+            for (int j = 0; j < artLines[i].length(); j++) {
+                if (artLines[i].charAt(j) == marker1) {
+                    System.out.println("Marker '" + marker1 + "' found at position: (" + i + ", " + j + ")");
+                }
+                if (artLines[i].charAt(j) == marker2) {
+                    System.out.println("Marker '" + marker2 + "' found at position: (" + i + ", " + j + ")");
+                }
+            }
+        }
+    }
+
+
+
+    /******************************************************************************************************************
+     * Locations of the markers as well as the desired new string are passed in to overwrite with time, date, coord.  *
+     *****************************************************************************************************************/
+
+    public void OVER_WRITE(String marker1, String replacement1, String marker2,
+                           String replacement2, String marker3, String replacement3) {
+        textSpans.clear();
+        for (int i = 0; i < artLines.length; i++) {
+            recordTextSpan(i, marker1, replacement1);
+            recordTextSpan(i, marker2, replacement2);
+            recordTextSpan(i, marker3, replacement3);
+            artLines[i] = replaceSubstring(artLines[i], marker1, replacement1);
+            artLines[i] = replaceSubstring(artLines[i], marker2, replacement2);
+            artLines[i] = replaceSubstring(artLines[i], marker3, replacement3);
+        }
+    }
+
+
+    private void recordTextSpan(int row, String marker, String replacement) {
+        int startIndex = artLines[row].indexOf(marker);
+        if (startIndex != -1) {
+            int length = Math.min(replacement.length(), artLines[row].length() - startIndex);
+            textSpans.add(new int[]{row, startIndex, length});
+        }
+    }
+
+    private boolean isTextCell(int row, int column) {
+        for (int[] span : textSpans) {
+            if (span[0] == row && column >= span[1] && column < span[1] + span[2]) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    /********************************************************************************
+     * Logic to input actual time, date, and coordinates on their respective markers *
+     ********************************************************************************/
+
+    private String replaceSubstring(String str, String marker, String replacement) {
+        int startIndex = str.indexOf(marker);
+        if (startIndex == -1) {
+            return str;
+        }
+
+        StringBuilder newStr = new StringBuilder(str);
+
+        // Replace characters starting from the marker position
+        int replacementIndex = 0;
+        for (int i = startIndex; i < str.length() && replacementIndex < replacement.length(); i++) {
+            newStr.setCharAt(i, replacement.charAt(replacementIndex));
+            replacementIndex++;
+        }
+
+        return newStr.toString();
+    }
+
+
+    /*****************************************
+     * Print out every character in the array *
+     *****************************************/
+    public void displayArt() {
+        writeFrame(layoutFrame(), "", "");
+    }
+
+    /*************************************************************
+     * --fade-in FRAMES, on startup the art materializes out of a  *
+     * blank screen over FADE_IN_MILLIS, dissolved in the same way *
+     * as a --blend. The same seed every step means a cell, once   *
+     * shown, stays. --fade-in 0 leaves the art to pop in.         *
+     *************************************************************/
+
+    public static List<String[]> fadeInFrames(String[] art, int steps) {
+        List<String[]> frames = new ArrayList<>();
+        for (int step = 1; step <= steps; step++) {
+            frames.add(blendArt(new String[0], art, (double) step / (steps + 1), new Random(DEMO_SEED)));
+        }
+        return frames;
+    }
+
+    public void fadeIn() throws InterruptedException {
+        String[] art = artLines;
+        for (String[] frame : fadeInFrames(art, fadeInSteps)) {
+            artLines = frame;
+            writeFrame(layoutFrame(), CLEAR_SCREEN, "");
+            Thread.sleep(FADE_IN_MILLIS / (fadeInSteps + 1));
+        }
+        artLines = art;
+        previousFrame = null; // The next frame is drawn in full
+        if (fadeInSteps > 0) {
+            System.out.print(CLEAR_SCREEN); // displayArt carries on from the top
+        }
+    }
+
+    /****************************************************************
+     * The whole frame is built up front and handed to stdout in one *
+     * print, slow terminals no longer tear halfway through a frame. *
+     ****************************************************************/
+
+    private void writeFrame(List<String> frame, String prefix, String suffix) {
+        if (dayOffset != 0) {
+            frame.add(0, ">>> TIME MACHINE " + currentDate() + " (" + (dayOffset > 0 ? "+" : "") + dayOffset
+                    + " days)  PageUp/PageDown to travel, Home to return <<<");
+        }
+        if (bannerFrames > 0) {
+            frame.add(0, banner);
+        }
+        if (skipUnchanged) {
+            // Nothing twinkled and the clock text didn't move on, the terminal already shows this frame
+            String drawn = prefix + String.join("\n", frame) + suffix;
+            if (drawn.equals(lastDrawn)) {
+                return;
+            }
+            lastDrawn = drawn;
+        }
+        if (doubleBuffer && CLEAR_SCREEN.equals(prefix) && previousFrame != null) {
+            System.out.print(String.join("", diffFrame(previousFrame, frame)));
+        } else {
+            System.out.print(prefix + buildFrame(frame) + suffix);
+        }
+        // Frames not drawn from the top left, or with a suffix (the inverted highlight), get redrawn in full
+        previousFrame = doubleBuffer && prefix.startsWith(CLEAR_SCREEN) && suffix.isEmpty() ? new ArrayList<>(frame) : null;
+        System.out.flush();
+        recordFrame(frame, clock.millis());
+    }
+
+    /*************************************************************
+     * --double-buffer, only the lines that differ from the last   *
+     * frame are rewritten, each one moved to with a cursor escape *
+     * and cleared to the end. Lines the new frame lost are wiped. *
+     *************************************************************/
+
+    public static List<String> diffFrame(List<String> previous, List<String> next) {
+        List<String> updates = new ArrayList<>();
+        for (int i = 0; i < Math.max(previous.size(), next.size()); i++) {
+            String before = i < previous.size() ? previous.get(i) : null;
+            String after = i < next.size() ? next.get(i) : "";
+            if (!after.equals(before)) {
+                updates.add("\033[" + (i + 1) + ";1H" + after + "\033[K");
+            }
+        }
+        return updates;
+    }
+
+    public static String buildFrame(List<String> frame) {
+        StringBuilder buffer = new StringBuilder();
+        for (String line : frame) {
+            buffer.append(line).append(System.lineSeparator());
+        }
+        return buffer.toString();
+    }
+
+    /*****************************************************************
+     * Appends a frame to the --record file. A failed write stops the *
+     * recording instead of taking the clock down with it. Nothing    *
+     * is written once shutdown has closed the file.                  *
+     *****************************************************************/
+
+    public synchronized void recordFrame(List<String> frame, long timestamp) {
+        if (recorder == null) {
+            return;
+        }
+        try {
+            recorder.write("@" + timestamp + " " + frame.size());
+            recorder.newLine();
+            for (String line : frame) {
+                recorder.write(line);
+                recorder.newLine();
+            }
+            recorder.flush();
+        } catch (IOException e) {
+            System.err.println("Error recording frame, recording stopped: " + e.getMessage());
+            recorder = null;
+        }
+    }
+
+    public void startRecording(String path) throws IOException {
+        recorder = Files.newBufferedWriter(Paths.get(path), StandardOpenOption.CREATE, StandardOpenOption.APPEND);
+    }
+
+    /*****************************************************************
+     * --csv log, one row per frame of timestamp,angle,season,art_file *
+     * flushed every CSV_FLUSH_FRAMES rows. Like the recording, a      *
+     * failed write stops the log and the display carries on.          *
+     *****************************************************************/
+
+    public void startCsv(String path) throws IOException {
+        boolean fresh = !Files.exists(Paths.get(path)) || Files.size(Paths.get(path)) == 0;
+        csvWriter = Files.newBufferedWriter(Paths.get(path), StandardOpenOption.CREATE, StandardOpenOption.APPEND);
+        if (fresh) {
+            csvWriter.write("timestamp,angle,season,art_file");
+            csvWriter.newLine();
+        }
+    }
+
+    /*****************************************************************
+     * Run on every way out, q, Ctrl+C, a kill or the end of main. The *
+     * CSV rows since the last flush would be lost otherwise. Every    *
+     * writer is closed on its own so one failing doesn't keep the     *
+     * rest open, then the art cache and the archive are let go.       *
+     * Waits for a frame being drawn so none of it is cut off, and the *
+     * writers check for null under the same lock, so the key thread   *
+     * can't write to a log closed under it.                           *
+     *****************************************************************/
+
+    public synchronized void shutdown() {
+        csvWriter = closeQuietly(csvWriter);
+        eventLog = closeQuietly(eventLog);
+        recorder = closeQuietly(recorder);
+        artCache.clear();
+        if (artArchive != null) {
+            try {
+                artArchive.close();
+            } catch (IOException e) {
+                // Nothing left to do on the way out
+            }
+            artArchive = null;
+        }
+    }
+
+    private static BufferedWriter closeQuietly(BufferedWriter writer) {
+        if (writer != null) {
+            try {
+                writer.close();
+            } catch (IOException e) {
+                // Nothing left to do on the way out
+            }
+        }
+        return null;
+    }
+
+    /*****************************************************************
+     * --event-log, a line per key press of timestamp, key and the     *
+     * action it set off ("none" for unbound keys), tab separated.    *
+     * Presses are rare so every line is flushed straight away.       *
+     *****************************************************************/
+
+    public void startEventLog(String path) throws IOException {
+        eventLog = Files.newBufferedWriter(Paths.get(path), StandardOpenOption.CREATE, StandardOpenOption.APPEND);
+    }
+
+    public static String eventLine(ZonedDateTime timestamp, String key, Action action) {
+        return timestamp.format(DateTimeFormatter.ISO_OFFSET_DATE_TIME) + "\tkey " + keyName(key) + "\t"
+                + (action == null ? "none" : action.name().toLowerCase().replace('_', '-'));
+    }
+
+    private synchronized void logEvent(String key, Action action) {
+        if (eventLog == null) {
+            return;
+        }
+        try {
+            eventLog.write(eventLine(ZonedDateTime.now(clock), key, action));
+            eventLog.newLine();
+            eventLog.flush();
+        } catch (IOException e) {
+            System.err.println("Error writing event log, logging stopped: " + e.getMessage());
+            eventLog = null;
+        }
+    }
+
+    public static String csvRow(ZonedDateTime timestamp, double angle, String season, String artFile) {
+        return timestamp.format(DateTimeFormatter.ISO_OFFSET_DATE_TIME) + "," + String.format("%.4f", angle) + ","
+                + season + "," + csvField(artFile == null ? "" : artFile);
+    }
+
+    private static String csvField(String value) {
+        if (value.contains(",") || value.contains("\"")) {
+            return "\"" + value.replace("\"", "\"\"") + "\"";
+        }
+        return value;
+    }
+
+    private synchronized void logCsv(LocalDate date, double angle) {
+        if (csvWriter == null) {
+            return;
+        }
+        try {
+            csvWriter.write(csvRow(ZonedDateTime.now(clock).plusDays(dayOffset), angle, season(date), currentArtPath));
+            csvWriter.newLine();
+            if (frameCount % CSV_FLUSH_FRAMES == 0) {
+                csvWriter.flush();
+            }
+        } catch (IOException e) {
+            System.err.println("Error writing CSV, logging stopped: " + e.getMessage());
+            csvWriter = null;
+        }
+    }
+
+    /**************************************************************
+     * Plays a recorded session back, waiting between frames for   *
+     * as long as the original session did.                        *
+     **************************************************************/
+
+    public void replay(String path) throws IOException, InterruptedException {
+        List<String> lines = Files.readAllLines(Paths.get(path));
+        long previous = -1;
+        int i = 0;
+        while (i < lines.size()) {
+            long[] header = replayHeader(lines.get(i), i + 1);
+            long timestamp = header[0];
+            int count = (int) header[1];
+            if (i + 1 + count > lines.size()) {
+                throw new IOException("Line " + (i + 1) + ": frame of " + count + " lines runs past the end of the file");
+            }
+            if (previous != -1 && timestamp > previous) {
+                Thread.sleep(timestamp - previous);
+            }
+            previous = timestamp;
+            clearScreen();
+            for (String line : lines.subList(i + 1, Math.min(lines.size(), i + 1 + count))) {
+                System.out.println(line);
+            }
+            i += count + 1;
+        }
+    }
+
+    private static long[] replayHeader(String line, int lineNumber) throws IOException {
+        String[] header = line.startsWith("@") ? line.substring(1).split(" ") : new String[0];
+        if (header.length != 2) {
+            throw new IOException("Line " + lineNumber + ": expected a \"@MILLIS LINES\" frame header but got: " + line);
+        }
+        try {
+            long timestamp = Long.parseLong(header[0]);
+            int count = Integer.parseInt(header[1]);
+            if (timestamp < 0 || count < 0) {
+                throw new NumberFormatException();
+            }
+            return new long[]{timestamp, count};
+        } catch (NumberFormatException e) {
+            throw new IOException("Line " + lineNumber + ": frame header numbers are not valid: " + line);
+        }
+    }
+
+    /*******************************************************************
+     * Fits the frame to the canvas set by --width/--height. The art is *
+     * centered, rows and columns past the edges are clipped and short  *
+     * lines are padded so every line is exactly the canvas width.      *
+     *******************************************************************/
 
-        // Earth moves roughly 360/365.25 degrees per day along its orbit
-        return (daysBetween * 360.0 / 365.25) % 360;
+    public List<String> layoutFrame() {
+        List<String> frame = new ArrayList<>();
+        int rows = artLines.length;
+        int height = canvasHeight > 0 ? canvasHeight : rows + paddingTop + paddingBottom;
+        int[] content = contentArea(height, paddingTop, paddingBottom);
+        int firstRow = Math.max(0, (rows - content[1]) / 2);
+        int topPad = content[0] + Math.max(0, (content[1] - rows) / 2);
+        for (int r = 0; r < height; r++) {
+            int source = r - topPad + firstRow;
+            if (r < content[0] || r >= content[0] + content[1]) {
+                source = -1;
+            }
+            String line = source < 0 || source >= rows ? " ".repeat(Math.max(0, canvasWidth)) : layoutLine(source);
+            if (skyGradient && colorEnabled) {
+                int[][] sky = palette.gradient();
+                line = colorEscape(gradientColor(r, height, sky[0], sky[1]), colorMode, true) + line + "\033[49m";
+            }
+            frame.add(line);
+        }
+        return frame;
     }
 
+    /*************************************************************
+     * Rows left for the art once the padding is taken off, as     *
+     * {first row, row count}. Padding never eats the whole frame. *
+     *************************************************************/
 
-    /*************************
-     *   HEART OF ASCII ART   *
-     *************************/
-    public Spacerace(){
+    public static int[] contentArea(int height, int top, int bottom) {
+        int first = Math.min(top, height);
+        return new int[]{first, Math.max(0, height - first - bottom)};
+    }
 
-        degreeArtMap.put(0,   "asciiArt/space010.txt");
-        degreeArtMap.put(20,  "asciiArt/space2030.txt");
-        degreeArtMap.put(40,  "asciiArt/space4050.txt");
-        degreeArtMap.put(60,  "asciiArt/space6070.txt");
-        degreeArtMap.put(90,  "asciiArt/space90110.txt");
-        degreeArtMap.put(120, "asciiArt/space120130.txt");
-        degreeArtMap.put(140, "asciiArt/space140150.txt");
-        degreeArtMap.put(160, "asciiArt/space160170.txt");
-        degreeArtMap.put(180, "asciiArt/space180190.txt");
-        degreeArtMap.put(200, "asciiArt/space200210.txt");
-        degreeArtMap.put(220, "asciiArt/space220230.txt");
-        degreeArtMap.put(240, "asciiArt/space240260.txt");
-        degreeArtMap.put(270, "asciiArt/space270290.txt");
-        degreeArtMap.put(300, "asciiArt/space300310.txt");
-        degreeArtMap.put(320, "asciiArt/space32030.txt");
-        degreeArtMap.put(340, "asciiArt/space34050.txt");
+    private String layoutLine(int row) {
+        String line = artLines[row];
+        if (canvasWidth <= 0) {
+            return styleLine(row, line, 0);
+        }
+        int start = marqueeSpeed > 0 ? marqueeStart(line.length(), canvasWidth, marqueeOffset)
+                : Math.max(0, alignedColumn(canvasWidth, line.length(), alignment));
+        String visible = line.substring(start, Math.min(line.length(), start + canvasWidth));
+        int left = alignedColumn(visible.length(), canvasWidth, alignment);
+        return " ".repeat(left) + styleLine(row, visible, start)
+                + " ".repeat(canvasWidth - left - visible.length());
     }
 
-    /*************************************************
-     * Takes position calculated and finds the        *
-     * corresponding filepath in the Navigable Map    *
-     *************************************************/
+    /***************************************************************
+     * Column where content of the given width starts inside a      *
+     * space of the given width. Negative when the content is wider *
+     * which, flipped around, is the crop start for that alignment. *
+     ***************************************************************/
 
-    public void loadAsciiArtForCurrentPosition(double position) {
-        String filePath;
-        // Find the appropriate file for the current position
-        Integer key = degreeArtMap.floorKey((int) position);
-        if (key == null) {
-            key = 0; // Default to the first range if position is below the lowest key
+    public static int alignedColumn(int content, int space, Alignment alignment) {
+        switch (alignment) {
+            case LEFT:
+                return 0;
+            case RIGHT:
+                return space - content;
+            default:
+                return (space - content) / 2;
         }
-        filePath = degreeArtMap.get(key);
-        // Load the ASCII art from the file path
-        try {
-            asciiArt(filePath);
-        } catch (IOException e) {
-            System.out.println("Error loading ASCII art: " + e.getMessage());
+    }
+
+    /****************************************************************
+     * Window of an over-wide line at a marquee offset. The window   *
+     * slides from the left edge to the right edge and starts over,  *
+     * lines that already fit are returned whole.                    *
+     ****************************************************************/
+
+    public static int marqueeStart(int lineLength, int width, int offset) {
+        if (lineLength <= width) {
+            return 0;
         }
+        return offset % (lineLength - width + 1);
     }
 
-    /********************************************
-     * Creates the array from the file path      *
-     * specified. Needed for twinkling and clock. *
-     ********************************************/
+    public static String windowLine(String line, int width, int offset) {
+        int start = marqueeStart(line.length(), width, offset);
+        return line.substring(start, Math.min(line.length(), start + width));
+    }
 
-    public void asciiArt(String filePath) throws IOException {
-        List<String> lines = Files.readAllLines(Paths.get(filePath));
-        artLines = lines.toArray(new String[0]);
+    /*****************************************************************
+     * Maps the orbital angle to a star color, fully warm at          *
+     * perihelion and fully cool half an orbit later at aphelion.     *
+     *****************************************************************/
+
+    public static int[] starTint(double angle) {
+        double warmth = (Math.cos(Math.toRadians(angle - PERIHELION_ANGLE)) + 1) / 2;
+        int[] tint = new int[3];
+        for (int i = 0; i < 3; i++) {
+            tint[i] = (int) Math.round(COOL_STAR[i] + (WARM_STAR[i] - COOL_STAR[i]) * warmth);
+        }
+        return tint;
     }
 
-    /********************************
-     * Starts the twinkling function.*
-     ********************************/
-    public void startTwinkling() {
-        Thread twinklingThread = new Thread(() -> {
-            try {
-                twinklingEffect();
-            } catch (InterruptedException e) {
-                Thread.currentThread().interrupt();
+    /*************************************************************
+     * Wraps each cell of a line in the palette's color escape.   *
+     * Stars are the cells that hold a star in the pristine art,  *
+     * text is whatever OVER_WRITE stamped and the rest of the    *
+     * box-drawing characters make up the border.                 *
+     *************************************************************/
+
+    private String styleLine(int row, String line, int offset) {
+        String[] stars = skyArt != null ? skyArt : originalArt;
+        if (!colorEnabled || (palette == Palette.PLAIN && artColors == null && logoFlashFrames == 0 && transitionFrames == 0) || stars == null
+                || row >= stars.length) {
+            return line;
+        }
+        StringBuilder styled = new StringBuilder();
+        for (int k = 0; k < line.length(); k++) {
+            char c = line.charAt(k);
+            int[] color = cellColor(row, k + offset, c);
+            if (color == null) {
+                styled.append(c);
+            } else {
+                styled.append(colorEscape(color, colorMode)).append(c).append("\033[39m");
             }
-        });
-        twinklingThread.start();
+        }
+        return styled.toString();
     }
 
-    /************************************************************************************
-     * Searches for my target characters which represent stars and slowly replaces them *
-     * with different characters to simulate twinkling. The clear screen function is    *
-     * called to keep the presentation focused on the current character array.          *
-     ***********************************************************************************/
+    /**************************************************************
+     * Terminal color support. COLORTERM announces truecolor, TERM  *
+     * tells 256 color terminals apart from plain 16 color ones.    *
+     **************************************************************/
 
+    public static boolean noColorUnset(String noColor) {
+        return noColor == null || noColor.isEmpty();
+    }
 
-    public void twinklingEffect() throws InterruptedException {
-        Random random = new Random();
-        char[] starFadeChars = {'*', '+', '.', ' '};
-        char[] plusFadeChars = {'┼', '├', '─', ' '};
-        int maxFadeIndex = starFadeChars.length - 1;
-        int maxPlusFadeIndex = plusFadeChars.length -1;
-        String[] originalArt = Arrays.copyOf(artLines, artLines.length);
-
-        DateTimeFormatter mediumClockedIn = DateTimeFormatter.ofPattern("HH:mm");
-        LocalDate date = LocalDate.now();
-        double position = calculateEarthPosition(date);
-        String formattedDate = date.format(DateTimeFormatter.ofPattern("yyyy-MM-dd"));
-        //infinite loop, the stars will shine until the program is terminated
-        while (true) {
-            clearScreen();
-            for (int j = 0; j < artLines.length; j++) {
-                char[] lineChars = artLines[j].toCharArray();
-                for (int k = 0; k < lineChars.length; k++) {
-                    if (originalArt[j].charAt(k) == '*' && random.nextFloat() < 0.4) {
-                        int fadeIndex = random.nextInt(maxFadeIndex + 1);
-                        lineChars[k] = starFadeChars[fadeIndex];
-                    }
-                    else if (originalArt[j].charAt(k) == '┼' && random.nextFloat() < 0.4) {
-                        int fadeIndex = random.nextInt(maxPlusFadeIndex + 1);
-                        lineChars[k] = plusFadeChars[fadeIndex];
-                    }
-                }
-                artLines[j] = new String(lineChars);
-            }
-            LocalTime updatedTime = LocalTime.now();
-            String updatedFormattedTime = updatedTime.truncatedTo(ChronoUnit.MINUTES).format(mediumClockedIn);
-            OVER_WRITE("&", String.valueOf(position), "=",
-                    formattedDate, "#", updatedFormattedTime);
+    public static ColorMode detectColorMode(String colorTerm, String term) {
+        if (colorTerm != null && (colorTerm.equalsIgnoreCase("truecolor") || colorTerm.equalsIgnoreCase("24bit"))) {
+            return ColorMode.TRUECOLOR;
+        }
+        if (term != null && term.contains("256")) {
+            return ColorMode.ANSI256;
+        }
+        return ColorMode.ANSI16;
+    }
+
+    public static String colorEscape(int[] rgb, ColorMode mode) {
+        return colorEscape(rgb, mode, false);
+    }
 
-            displayArt();
-            Thread.sleep(1000); // Adjust for desired speed
-            artLines = Arrays.copyOf(originalArt, originalArt.length);
+    public static String colorEscape(int[] rgb, ColorMode mode, boolean background) {
+        int base = background ? 40 : 30;
+        switch (mode) {
+            case TRUECOLOR:
+                return "\033[" + (base + 8) + ";2;" + rgb[0] + ";" + rgb[1] + ";" + rgb[2] + "m";
+            case ANSI256:
+                return "\033[" + (base + 8) + ";5;" + rgbTo256(rgb[0], rgb[1], rgb[2]) + "m";
+            default:
+                int index = rgbTo16(rgb[0], rgb[1], rgb[2]);
+                return "\033[" + (index < 8 ? base + index : base + 60 + index - 8) + "m";
         }
     }
 
+    /**************************************************************
+     * Background color of a row, a straight blend from the top    *
+     * color on the first row to the bottom color on the last.     *
+     **************************************************************/
+
+    public static int[] gradientColor(int row, int rows, int[] top, int[] bottom) {
+        double t = rows <= 1 ? 0 : (double) row / (rows - 1);
+        int[] color = new int[3];
+        for (int i = 0; i < 3; i++) {
+            color[i] = (int) Math.round(top[i] + (bottom[i] - top[i]) * t);
+        }
+        return color;
+    }
 
     /***************************************************************
-     * Clears the previous string array output to help with format. *
+     * Nearest xterm 256 color, either a cell of the 6x6x6 cube or  *
+     * one of the 24 grays, whichever lands closer to the original. *
      ***************************************************************/
 
+    public static int rgbTo256(int r, int g, int b) {
+        int[] levels = {0, 95, 135, 175, 215, 255};
+        int ri = nearestLevel(r, levels);
+        int gi = nearestLevel(g, levels);
+        int bi = nearestLevel(b, levels);
+        int cube = 16 + 36 * ri + 6 * gi + bi;
+        int cubeDistance = distance(r, g, b, levels[ri], levels[gi], levels[bi]);
 
-    private void clearScreen() {
-        // Clear the console - this method is platform-dependent.
-        // For Unix/Linux/Mac:
-        System.out.print("\033[H\033[2J");
-        System.out.flush();
+        int grayIndex = Math.max(0, Math.min(23, Math.round(((r + g + b) / 3f - 8) / 10f)));
+        int grayLevel = 8 + 10 * grayIndex;
+        int grayDistance = distance(r, g, b, grayLevel, grayLevel, grayLevel);
+        return grayDistance < cubeDistance ? 232 + grayIndex : cube;
     }
 
-    /******************************************************************************
-     * Finds the positions of two specified markers in the array. Use this to find *
-     * and mark locations in the array for desired changes.                        *
-     ******************************************************************************/
-    public void findMarkerPositions(char marker1, char marker2) {
-        for (int i = 0; i < artLines.length; i++) {
-            for (int j = 0; j < artLines[i].length(); j++) {
-                if (artLines[i].charAt(j) == marker1) {
-                    System.out.println("Marker '" + marker1 + "' found at position: (" + i + ", " + j + ")");
-                }
-                if (artLines[i].charAt(j) == marker2) {
-                    System.out.println("Marker '" + marker2 + "' found at position: (" + i + ", " + j + ")");
-                }
+    public static int rgbTo16(int r, int g, int b) {
+        int best = 0;
+        for (int i = 1; i < ANSI16_COLORS.length; i++) {
+            int[] c = ANSI16_COLORS[i];
+            int[] current = ANSI16_COLORS[best];
+            if (distance(r, g, b, c[0], c[1], c[2]) < distance(r, g, b, current[0], current[1], current[2])) {
+                best = i;
             }
         }
+        return best;
     }
 
+    private static int nearestLevel(int value, int[] levels) {
+        int best = 0;
+        for (int i = 1; i < levels.length; i++) {
+            if (Math.abs(levels[i] - value) < Math.abs(levels[best] - value)) {
+                best = i;
+            }
+        }
+        return best;
+    }
 
+    private static int distance(int r1, int g1, int b1, int r2, int g2, int b2) {
+        return (r1 - r2) * (r1 - r2) + (g1 - g2) * (g1 - g2) + (b1 - b2) * (b1 - b2);
+    }
 
-    /******************************************************************************************************************
-     * Locations of the markers as well as the desired new string are passed in to overwrite with time, date, coord.  *
-     *****************************************************************************************************************/
+    private int[] cellColor(int row, int column, char c) {
+        String[] stars = skyArt != null ? skyArt : originalArt;
+        if (!colorEnabled || stars == null || row >= stars.length) {
+            return null;
+        }
+        int[] color = paletteColor(palette, currentPosition, stars, row, column, c);
+        if (transitionFrames > 0) {
+            int[] from = paletteColor(fromPalette, fromPosition, stars, row, column, c);
+            color = lerpColor(from, color, 1 - (double) transitionFrames / (colorFadeFrames + 1));
+        }
+        return color;
+    }
 
-    public void OVER_WRITE(String marker1, String replacement1, String marker2,
-                           String replacement2, String marker3, String replacement3) {
-        for (int i = 0; i < artLines.length; i++) {
-            artLines[i] = replaceSubstring(artLines[i], marker1, replacement1);
-            artLines[i] = replaceSubstring(artLines[i], marker2, replacement2);
-            artLines[i] = replaceSubstring(artLines[i], marker3, replacement3);
+    private int[] paletteColor(Palette palette, double position, String[] stars, int row, int column, char c) {
+        if (logoFlashFrames > 0 && isLogoCell(row, column)) {
+            return LOGO_CORNER_COLOR;
+        } else if (isTextCell(row, column)) {
+            return palette.textColor();
+        } else if (artColors != null && row < artColors.length && column < artColors[row].length
+                && artColors[row][column] != null) {
+            return artColors[row][column];
+        } else if (column < stars[row].length() && isStarSource(stars[row].charAt(column))) {
+            return c != ' ' ? palette.starColor(c, position) : null;
+        } else if (c >= '\u2500' && c <= '\u257F') {
+            return palette.borderColor();
         }
+        return null;
     }
 
+    /*************************************************************
+     * Fade from the old colors to the new over --color-fade       *
+     * frames after a theme change or a bucket crossing. A cell    *
+     * without a color fades from or to TERMINAL_DEFAULT_COLOR.    *
+     *************************************************************/
 
-    /********************************************************************************
-     * Logic to input actual time, date, and coordinates on their respective markers *
-     ********************************************************************************/
+    private void startColorTransition(Palette from) {
+        if (colorFadeFrames > 0) {
+            fromPalette = from;
+            fromPosition = currentPosition;
+            transitionFrames = colorFadeFrames;
+        }
+    }
 
-    private String replaceSubstring(String str, String marker, String replacement) {
-        int startIndex = str.indexOf(marker);
-        if (startIndex == -1) {
-            return str;
+    // How bright a twinkle glyph reads, the full star 1 down to the faintest fade step
+    public static double glyphBrightness(char glyph) {
+        switch (glyph) {
+            case '*': case '┼': return 1.0;
+            case '+': case '├': case '|': return 0.6;
+            case '.': case '─': case '-': return 0.2;
+            default: return 0;
         }
+    }
 
-        StringBuilder newStr = new StringBuilder(str);
+    // Brightness 0 to 1 along STAR_RAMP, blended between its neighbouring stops
+    public static int[] rampColor(double brightness) {
+        double scaled = Math.max(0, Math.min(1, brightness)) * (STAR_RAMP.length - 1);
+        int low = Math.min((int) scaled, STAR_RAMP.length - 2);
+        return lerpColor(STAR_RAMP[low], STAR_RAMP[low + 1], scaled - low);
+    }
 
-        // Replace characters starting from the marker position
-        int replacementIndex = 0;
-        for (int i = startIndex; i < str.length() && replacementIndex < replacement.length(); i++) {
-            newStr.setCharAt(i, replacement.charAt(replacementIndex));
-            replacementIndex++;
+    public static int[] lerpColor(int[] from, int[] to, double t) {
+        if (from == null && to == null) {
+            return null;
         }
+        int[] a = from != null ? from : TERMINAL_DEFAULT_COLOR;
+        int[] b = to != null ? to : TERMINAL_DEFAULT_COLOR;
+        int[] mixed = new int[3];
+        for (int i = 0; i < 3; i++) {
+            mixed[i] = (int) Math.round(a[i] + (b[i] - a[i]) * t);
+        }
+        return mixed;
+    }
 
-        return newStr.toString();
+    // A + only ever sits in the pristine art as the --ascii-only stand in for ┼
+    private static boolean isStarSource(char c) {
+        return c == '*' || c == '┼' || c == '+';
     }
 
+    /*************************************************************
+     * --ascii-only for terminals without box-drawing glyphs,      *
+     * crossings and corners turn into +, the lines into | and -.  *
+     *************************************************************/
 
-    /*****************************************
-     * Print out every character in the array *
-     *****************************************/
-    public void displayArt() {
-        for (String line : artLines) {
-            System.out.println(line);
+    public static char asciiFallback(char c) {
+        if (c == '│' || c == '┃' || c == '║') {
+            return '|';
+        } else if (c == '─' || c == '━' || c == '═') {
+            return '-';
+        } else if (c >= '\u2500' && c <= '\u257F') {
+            return '+';
+        } else if (c == '░') {
+            return '.';
+        } else if (c >= '\u2580' && c <= '\u259F') {
+            return '#';
+        }
+        return c;
+    }
+
+    public static String toAscii(String line) {
+        char[] chars = line.toCharArray();
+        for (int i = 0; i < chars.length; i++) {
+            chars[i] = asciiFallback(chars[i]);
+        }
+        return new String(chars);
+    }
+
+    private void useAsciiOnly() {
+        plusStar = asciiFallback(plusStar);
+        for (int i = 0; i < plusFadeChars.length; i++) {
+            plusFadeChars[i] = asciiFallback(plusFadeChars[i]);
+            starFadeChars[i] = asciiFallback(starFadeChars[i]);
         }
     }
 
@@ -265,10 +3949,298 @@ public class Spacerace {
      *********************************************************************/
 
 
-    private Set<Integer> ParseCmdLine(String[] args) {
+    // Package private so the tests can set a Spacerace up from flags the way main does
+    Set<Integer> ParseCmdLine(String[] args) {
         Set<Integer> rowsToRemoveSet = new HashSet<>();
+        boolean artMapped = false;
         for (int i = 0; i < args.length; i++) {
-            if ("-m".equals(args[i]) && i + 1 < args.length) {
+            if ("--background".equals(args[i]) && i + 1 < args.length) {
+                backgroundPath = args[i + 1];
+                i++;
+            } else if ("--ramp".equals(args[i]) && i + 1 < args.length) {
+                if (args[i + 1].length() < 2) {
+                    throw new IllegalArgumentException("--ramp needs at least two characters: " + args[i + 1]);
+                }
+                for (char c : args[i + 1].toCharArray()) {
+                    if (RESERVED_RAMP_CHARS.indexOf(c) >= 0) {
+                        throw new IllegalArgumentException("--ramp can't use the star glyphs or the & = # markers: "
+                                + args[i + 1]);
+                    }
+                }
+                backgroundRamp = args[i + 1];
+                i++;
+            } else if ("--max-gap".equals(args[i]) && i + 1 < args.length) {
+                maxGapDegrees = parsePositive("--max-gap", args[i + 1]);
+                i++;
+            } else if ("--wind".equals(args[i]) && i + 1 < args.length) {
+                String[] direction = args[i + 1].split(",");
+                if (direction.length != 2) {
+                    throw new IllegalArgumentException("Expected --wind DX,DY but got: " + args[i + 1]);
+                }
+                windX = Double.parseDouble(direction[0]);
+                windY = Double.parseDouble(direction[1]);
+                i++;
+            } else if ("--fade-in".equals(args[i]) && i + 1 < args.length) {
+                fadeInSteps = Integer.parseInt(args[i + 1]);
+                if (fadeInSteps < 0) {
+                    throw new IllegalArgumentException("--fade-in can't be negative: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--shimmer".equals(args[i])) {
+                shimmer = true;
+            } else if ("--skip-unchanged".equals(args[i])) {
+                skipUnchanged = true;
+            } else if ("--flip-h".equals(args[i])) {
+                flipHorizontal = true;
+            } else if ("--flip-v".equals(args[i])) {
+                flipVertical = true;
+            } else if ("--twinkle-gradient".equals(args[i]) && i + 1 < args.length) {
+                String[] ends = args[i + 1].split(":");
+                if (ends.length != 2) {
+                    throw new IllegalArgumentException("Expected --twinkle-gradient TOP:BOTTOM but got: " + args[i + 1]);
+                }
+                gradientTop = Double.parseDouble(ends[0]);
+                gradientBottom = Double.parseDouble(ends[1]);
+                if (gradientTop < 0 || gradientBottom < 0) {
+                    throw new IllegalArgumentException("--twinkle-gradient factors can't be negative: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--art".equals(args[i]) && i + 1 < args.length) {
+                if (!artMapped) {
+                    degreeArtMap.clear();
+                    artMapped = true;
+                }
+                putArtMapping(degreeArtMap, args[i + 1]);
+                i++;
+            } else if ("--no-art".equals(args[i])) {
+                // An empty map, only the generated starfield is shown
+                degreeArtMap.clear();
+                artMapped = true;
+            } else if ("--show-config".equals(args[i])) {
+                showConfig = true;
+            } else if ("--calendar-year".equals(args[i])) {
+                calendarYear = true;
+            } else if ("--borderless".equals(args[i])) {
+                borderless = true;
+            } else if ("--bell".equals(args[i])) {
+                bellOnChange = true;
+            } else if ("--scan".equals(args[i])) {
+                scanMode = true;
+            } else if ("--schedule".equals(args[i]) && i + 1 < args.length) {
+                scheduleHours = parsePositive("--schedule", args[i + 1]);
+                i++;
+            } else if ("--version".equals(args[i])) {
+                showVersion = true;
+            } else if ("--placeholder".equals(args[i]) && i + 1 < args.length) {
+                placeholder = args[i + 1];
+                i++;
+            } else if ("--doctor".equals(args[i])) {
+                doctorMode = true;
+            } else if ("--double-buffer".equals(args[i])) {
+                doubleBuffer = true;
+            } else if ("--keys".equals(args[i]) && i + 1 < args.length) {
+                keysPath = args[i + 1];
+                i++;
+            } else if ("--demo".equals(args[i])) {
+                demoMode = true;
+            } else if ("--once".equals(args[i])) {
+                onceMode = true;
+            } else if ("--cycle".equals(args[i]) && i + 1 < args.length) {
+                cycleSeconds = parsePositive("--cycle", args[i + 1]);
+                i++;
+            } else if ("--random".equals(args[i]) && i + 1 < args.length) {
+                cycleSeconds = parsePositive("--random", args[i + 1]);
+                randomScenes = true;
+                i++;
+            } else if ("--bucket-weights".equals(args[i]) && i + 1 < args.length) {
+                for (String part : args[i + 1].split(",")) {
+                    String[] weight = part.split(":");
+                    if (weight.length != 2) {
+                        throw new IllegalArgumentException("Expected DEG:WEIGHT but got: " + part);
+                    }
+                    bucketWeights.put(Integer.parseInt(weight[0].trim()), parsePositive("--bucket-weights", weight[1].trim()));
+                }
+                i++;
+            } else if ("--period".equals(args[i]) && i + 1 < args.length) {
+                orbitalPeriodDays = Double.parseDouble(args[i + 1]);
+                if (orbitalPeriodDays <= 0) {
+                    throw new IllegalArgumentException("--period must be a positive number of days: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--constellations".equals(args[i]) && i + 1 < args.length) {
+                constellationsPath = args[i + 1];
+                i++;
+            } else if ("--bodies".equals(args[i]) && i + 1 < args.length) {
+                bodiesPath = args[i + 1];
+                i++;
+            } else if ("--grid".equals(args[i]) && i + 1 < args.length) {
+                gridBodies = args[i + 1];
+                i++;
+            } else if ("--viewpoint".equals(args[i]) && i + 1 < args.length) {
+                viewpointName = args[i + 1];
+                i++;
+            } else if ("--body".equals(args[i]) && i + 1 < args.length) {
+                bodyName = args[i + 1];
+                i++;
+            } else if ("--smooth".equals(args[i]) && i + 1 < args.length) {
+                angleSmoothing = Double.parseDouble(args[i + 1]);
+                if (angleSmoothing <= 0 || angleSmoothing > 1) {
+                    throw new IllegalArgumentException("--smooth must be above 0 and at most 1: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--julian".equals(args[i])) {
+                statusSegments.put(StatusSegment.ANGLE, Alignment.LEFT);
+                statusSegments.put(StatusSegment.JULIAN, Alignment.LEFT);
+                statusSegments.put(StatusSegment.DAY, Alignment.LEFT);
+            } else if ("--fps-window".equals(args[i]) && i + 1 < args.length) {
+                fpsWindow = parsePositive("--fps-window", args[i + 1]);
+                i++;
+            } else if ("--status".equals(args[i]) && i + 1 < args.length) {
+                parseStatusSegments(args[i + 1]);
+                i++;
+            } else if ("--static".equals(args[i]) && i + 1 < args.length) {
+                // --static TOP,LEFT,BOTTOM,RIGHT, repeat the flag for more regions
+                String[] corners = args[i + 1].split(",");
+                if (corners.length != 4) {
+                    throw new IllegalArgumentException("Expected TOP,LEFT,BOTTOM,RIGHT but got: " + args[i + 1]);
+                }
+                addStaticRegion(Integer.parseInt(corners[0].trim()), Integer.parseInt(corners[1].trim()),
+                        Integer.parseInt(corners[2].trim()), Integer.parseInt(corners[3].trim()));
+                i++;
+            } else if ("--lifetime".equals(args[i]) && i + 1 < args.length) {
+                starLifetime = parsePositive("--lifetime", args[i + 1]);
+                i++;
+            } else if ("--blend".equals(args[i]) && i + 1 < args.length) {
+                blendMargin = parsePositive("--blend", args[i + 1]);
+                i++;
+            } else if ("--opposite".equals(args[i])) {
+                showOpposite = true;
+            } else if ("--tint".equals(args[i])) {
+                palette = Palette.SEASONAL;
+            } else if ("--no-color".equals(args[i])) {
+                colorEnabled = false;
+            } else if ("--gradient".equals(args[i])) {
+                skyGradient = true;
+            } else if ("--color-mode".equals(args[i]) && i + 1 < args.length) {
+                colorMode = ColorMode.valueOf(args[i + 1].toUpperCase());
+                i++;
+            } else if ("--tab-width".equals(args[i]) && i + 1 < args.length) {
+                tabWidth = parsePositive("--tab-width", args[i + 1]);
+                i++;
+            } else if ("--trim".equals(args[i])) {
+                trimTrailing = true;
+            } else if ("--ascii-only".equals(args[i])) {
+                asciiOnly = true;
+            } else if ("--ansi-art".equals(args[i])) {
+                ansiArt = true;
+            } else if ("--color-fade".equals(args[i]) && i + 1 < args.length) {
+                colorFadeFrames = Integer.parseInt(args[i + 1]);
+                if (colorFadeFrames < 0) {
+                    throw new IllegalArgumentException("--color-fade can't be negative: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--themes".equals(args[i]) && i + 1 < args.length) {
+                themes.clear();
+                for (String theme : args[i + 1].split(",")) {
+                    themes.add(Palette.valueOf(theme.trim().toUpperCase()));
+                }
+                i++;
+            } else if ("--palette".equals(args[i]) && i + 1 < args.length) {
+                palette = Palette.valueOf(args[i + 1].toUpperCase());
+                i++;
+            } else if ("--sun".equals(args[i])) {
+                showSun = true;
+            } else if ("--orbit-dial".equals(args[i])) {
+                showOrbitDial = true;
+            } else if ("--terminator".equals(args[i])) {
+                showTerminator = true;
+            } else if ("--interval".equals(args[i]) && i + 1 < args.length) {
+                frameMillis = Long.parseLong(args[i + 1]);
+                if (frameMillis < 0) {
+                    throw new IllegalArgumentException("--interval can't be negative: " + args[i + 1]);
+                }
+                i++;
+            } else if ("--max-fps".equals(args[i]) && i + 1 < args.length) {
+                minFrameMillis = Math.max(1, 1000 / parsePositive("--max-fps", args[i + 1]));
+                i++;
+            } else if ("--idle".equals(args[i]) && i + 1 < args.length) {
+                idleAfter = Duration.ofSeconds(parsePositive("--idle", args[i + 1]));
+                i++;
+            } else if ("--moon-pulse".equals(args[i])) {
+                moonPulse = true;
+            } else if ("--phase-period".equals(args[i]) && i + 1 < args.length) {
+                phasePeriod = parsePositive("--phase-period", args[i + 1]);
+                i++;
+            } else if ("--trail".equals(args[i]) && i + 1 < args.length) {
+                trailLength = parsePositive("--trail", args[i + 1]);
+                i++;
+            } else if ("--padding".equals(args[i]) && i + 1 < args.length) {
+                String[] parts = args[i + 1].split(",");
+                paddingTop = Math.max(0, Integer.parseInt(parts[0].trim()));
+                paddingBottom = parts.length > 1 ? Math.max(0, Integer.parseInt(parts[1].trim())) : paddingTop;
+                i++;
+            } else if ("--iss".equals(args[i]) && i + 1 < args.length) {
+                // --iss ROW or --iss ROW,SPEED
+                String[] parts = args[i + 1].split(",");
+                showIss = true;
+                issRow = Integer.parseInt(parts[0]);
+                if (parts.length > 1) {
+                    issSpeed = parsePositive("--iss", parts[1].trim());
+                }
+                i++;
+            } else if ("--art-root".equals(args[i]) && i + 1 < args.length) {
+                artRoot = args[i + 1];
+                i++;
+            } else if ("--art-archive".equals(args[i]) && i + 1 < args.length) {
+                artArchivePath = args[i + 1];
+                i++;
+            } else if ("--max-art-bytes".equals(args[i]) && i + 1 < args.length) {
+                maxArtBytes = parsePositive("--max-art-bytes", args[i + 1]);
+                i++;
+            } else if ("--seed".equals(args[i]) && i + 1 < args.length) {
+                // Fixed seed for reproducible twinkling
+                random = new Random(Long.parseLong(args[i + 1]));
+                i++;
+            } else if ("--record".equals(args[i]) && i + 1 < args.length) {
+                recordPath = args[i + 1];
+                i++;
+            } else if ("--event-log".equals(args[i]) && i + 1 < args.length) {
+                eventLogPath = args[i + 1];
+                i++;
+            } else if ("--csv".equals(args[i]) && i + 1 < args.length) {
+                csvPath = args[i + 1];
+                i++;
+            } else if ("--replay".equals(args[i]) && i + 1 < args.length) {
+                replayPath = args[i + 1];
+                i++;
+            } else if ("--width".equals(args[i]) && i + 1 < args.length) {
+                canvasWidth = parsePositive("--width", args[i + 1]);
+                i++;
+            } else if ("--height".equals(args[i]) && i + 1 < args.length) {
+                canvasHeight = parsePositive("--height", args[i + 1]);
+                i++;
+            } else if ("--layout".equals(args[i]) && i + 1 < args.length) {
+                if (!"side".equals(args[i + 1]) && !"full".equals(args[i + 1])) {
+                    throw new IllegalArgumentException("--layout must be side or full: " + args[i + 1]);
+                }
+                sidePanel = "side".equals(args[i + 1]);
+                i++;
+            } else if ("--align".equals(args[i]) && i + 1 < args.length) {
+                alignment = Alignment.valueOf(args[i + 1].toUpperCase());
+                i++;
+            } else if ("--marquee".equals(args[i]) && i + 1 < args.length) {
+                marqueeSpeed = parsePositive("--marquee", args[i + 1]);
+                i++;
+            } else if ("--off-char".equals(args[i]) && i + 1 < args.length) {
+                parseOffChars(args[i + 1]);
+                i++;
+            } else if ("--star-weights".equals(args[i]) && i + 1 < args.length) {
+                starFadeWeights = parseWeights(args[i + 1], starFadeChars.length);
+                i++;
+            } else if ("--plus-weights".equals(args[i]) && i + 1 < args.length) {
+                plusFadeWeights = parseWeights(args[i + 1], plusFadeChars.length);
+                i++;
+            } else if ("-m".equals(args[i]) && i + 1 < args.length) {
                 // Assuming the -m option is followed by a list of row indices
                 String[] parts = args[i + 1].split(",");
                 for (String part : parts) {
@@ -283,11 +4255,221 @@ public class Spacerace {
                         rowsToRemoveSet.add(Integer.parseInt(part));
                     }
                 }
+                i++;
+            } else if (args[i].startsWith("--")) {
+                // Anything left starting with -- is a misspelled flag or one whose value is missing
+                throw new IllegalArgumentException("Unknown option or missing value: " + args[i]);
             }
         }
+        if (asciiOnly) {
+            useAsciiOnly();
+        }
+        for (int angle : bucketWeights.keySet()) {
+            // Checked once the loop is done, since --art may replace the map after the weights
+            if (!degreeArtMap.containsKey(angle)) {
+                throw new IllegalArgumentException("--bucket-weights names " + angle + "°, which is not in the art map");
+            }
+        }
+        if (cycleSeconds > 0) {
+            // Dwell is given in seconds and counted in frames
+            cycleDwellFrames = Math.max(1, cycleSeconds * 1000L / clampInterval(frameMillis, minFrameMillis));
+        }
         return rowsToRemoveSet;
     }
 
+    /******************************************************************
+     * Version line for bug reports. Plain javac has no build step to  *
+     * stamp the class, so the build time is when Spacerace.class was  *
+     * compiled and the commit is read from the checkout if there is   *
+     * one next to it.                                                 *
+     ******************************************************************/
+
+    public static String versionString() {
+        return "Spacerace " + VERSION + " (commit " + gitCommit() + ", built " + buildTime() + ")";
+    }
+
+    private static String buildTime() {
+        try {
+            Path classFile = Paths.get(Spacerace.class.getProtectionDomain().getCodeSource().getLocation().toURI())
+                    .resolve("Spacerace.class");
+            return Files.getLastModifiedTime(classFile).toInstant().truncatedTo(ChronoUnit.SECONDS).toString();
+        } catch (Exception e) {
+            return "unknown";
+        }
+    }
+
+    private static String gitCommit() {
+        try {
+            String head = Files.readString(Paths.get(".git", "HEAD")).trim();
+            if (head.startsWith("ref: ")) {
+                Path ref = Paths.get(".git", head.substring(5));
+                if (!Files.exists(ref)) {
+                    return "unknown";
+                }
+                head = Files.readString(ref).trim();
+            }
+            return head.substring(0, Math.min(7, head.length()));
+        } catch (IOException e) {
+            return "unknown";
+        }
+    }
+
+    /*************************************************************
+     * --show-config prints the settings in effect once defaults,  *
+     * environment, flags and the files they name are all applied, *
+     * as TOML. Settings with no value are left out.               *
+     *************************************************************/
+
+    public String configToml() {
+        StringBuilder toml = new StringBuilder();
+        toml.append("[timing]\n");
+        tomlEntry(toml, "frame_millis", frameMillis);
+        tomlEntry(toml, "min_frame_millis", minFrameMillis);
+        tomlEntry(toml, "cycle_seconds", cycleSeconds);
+        tomlEntry(toml, "random_scenes", randomScenes);
+        tomlEntry(toml, "schedule_hours", scheduleHours);
+        tomlEntry(toml, "day_offset", dayOffset);
+        tomlEntry(toml, "once", onceMode);
+        tomlEntry(toml, "paused", paused);
+        toml.append("\n[orbit]\n");
+        tomlEntry(toml, "pinned_angle", pinnedAngle);
+        tomlEntry(toml, "period_days", orbitalPeriodDays);
+        tomlEntry(toml, "calendar_year", calendarYear);
+        tomlEntry(toml, "body", bodyName);
+        tomlEntry(toml, "viewpoint", viewpointName);
+        tomlEntry(toml, "bodies", bodiesPath);
+        tomlEntry(toml, "angle_smoothing", angleSmoothing);
+        tomlEntry(toml, "blend_margin", blendMargin);
+        toml.append("\n[art]\n");
+        tomlEntry(toml, "root", artRoot);
+        tomlEntry(toml, "archive", artArchivePath);
+        tomlEntry(toml, "max_bytes", maxArtBytes);
+        tomlEntry(toml, "max_gap", maxGapDegrees);
+        tomlEntry(toml, "demo", demoMode);
+        tomlEntry(toml, "placeholder", placeholder);
+        tomlEntry(toml, "rows_removed", new TreeSet<>(rowsToRemove));
+        tomlEntry(toml, "ansi", ansiArt);
+        tomlEntry(toml, "ascii_only", asciiOnly);
+        tomlEntry(toml, "borderless", borderless);
+        tomlEntry(toml, "flip_h", flipHorizontal);
+        tomlEntry(toml, "flip_v", flipVertical);
+        tomlEntry(toml, "tab_width", tabWidth);
+        tomlEntry(toml, "trim_trailing", trimTrailing);
+        tomlEntry(toml, "constellations", constellationsPath);
+        tomlEntry(toml, "background", backgroundPath);
+        tomlEntry(toml, "ramp", backgroundRamp);
+        toml.append("\n[art.map]\n");
+        for (Map.Entry<Integer, String> mapping : degreeArtMap.entrySet()) {
+            tomlEntry(toml, "\"" + mapping.getKey() + "\"", mapping.getValue());
+        }
+        toml.append("\n[display]\n");
+        tomlEntry(toml, "color", colorEnabled);
+        tomlEntry(toml, "color_mode", colorMode);
+        tomlEntry(toml, "palette", palette);
+        tomlEntry(toml, "themes", themes);
+        tomlEntry(toml, "color_fade_frames", colorFadeFrames);
+        tomlEntry(toml, "fade_in_frames", fadeInSteps);
+        tomlEntry(toml, "brightness", brightness);
+        tomlEntry(toml, "moon_pulse", moonPulse);
+        tomlEntry(toml, "sky_gradient", skyGradient);
+        tomlEntry(toml, "canvas_width", canvasWidth);
+        tomlEntry(toml, "canvas_height", canvasHeight);
+        tomlEntry(toml, "alignment", alignment);
+        tomlEntry(toml, "padding_top", paddingTop);
+        tomlEntry(toml, "padding_bottom", paddingBottom);
+        tomlEntry(toml, "side_panel", sidePanel);
+        tomlEntry(toml, "marquee_speed", marqueeSpeed);
+        tomlEntry(toml, "double_buffer", doubleBuffer);
+        tomlEntry(toml, "skip_unchanged", skipUnchanged);
+        tomlEntry(toml, "status", statusSegments.keySet());
+        tomlEntry(toml, "fps_window", fpsWindow);
+        toml.append("\n[stars]\n");
+        tomlEntry(toml, "lifetime", starLifetime);
+        tomlEntry(toml, "trail_length", trailLength);
+        tomlEntry(toml, "phase_period", phasePeriod);
+        tomlEntry(toml, "shimmer", shimmer);
+        tomlEntry(toml, "wind", List.of(windX, windY));
+        tomlEntry(toml, "gradient_top", gradientTop);
+        tomlEntry(toml, "gradient_bottom", gradientBottom);
+        tomlEntry(toml, "star_weights", Arrays.stream(starFadeWeights).boxed().toList());
+        tomlEntry(toml, "plus_weights", Arrays.stream(plusFadeWeights).boxed().toList());
+        toml.append("\n[overlays]\n");
+        tomlEntry(toml, "iss", showIss);
+        tomlEntry(toml, "sun", showSun);
+        tomlEntry(toml, "terminator", showTerminator);
+        tomlEntry(toml, "orbit_dial", showOrbitDial);
+        tomlEntry(toml, "logo", showLogo);
+        tomlEntry(toml, "opposite", showOpposite);
+        tomlEntry(toml, "bell", bellOnChange);
+        toml.append("\n[files]\n");
+        tomlEntry(toml, "record", recordPath);
+        tomlEntry(toml, "replay", replayPath);
+        tomlEntry(toml, "csv", csvPath);
+        tomlEntry(toml, "event_log", eventLogPath);
+        tomlEntry(toml, "keys", keysPath);
+        tomlEntry(toml, "idle_after_seconds", idleAfter != null ? idleAfter.getSeconds() : null);
+        toml.append("\n[keys]\n");
+        Map<Action, Set<String>> keys = new EnumMap<>(Action.class);
+        for (Map.Entry<String, Action> binding : keyBindings.entrySet()) {
+            keys.computeIfAbsent(binding.getValue(), action -> new TreeSet<>()).add(keyName(binding.getKey()));
+        }
+        for (Map.Entry<Action, Set<String>> action : keys.entrySet()) {
+            tomlEntry(toml, action.getKey().name().toLowerCase().replace('_', '-'), action.getValue());
+        }
+        return toml.toString();
+    }
+
+    // The KEY_NAMES name a bound sequence came from, or the character itself
+    private static String keyName(String sequence) {
+        for (Map.Entry<String, List<String>> name : KEY_NAMES.entrySet()) {
+            if (name.getValue().contains(sequence)) {
+                return name.getKey();
+            }
+        }
+        return sequence;
+    }
+
+    private static void tomlEntry(StringBuilder toml, String key, Object value) {
+        if (value == null) {
+            return;
+        }
+        toml.append(key).append(" = ").append(tomlValue(value)).append('\n');
+    }
+
+    private static String tomlValue(Object value) {
+        if (value instanceof Boolean || value instanceof Number) {
+            return value.toString();
+        } else if (value instanceof Enum) {
+            return tomlValue(((Enum<?>) value).name().toLowerCase());
+        } else if (value instanceof Iterable) {
+            List<String> items = new ArrayList<>();
+            for (Object item : (Iterable<?>) value) {
+                items.add(tomlValue(item));
+            }
+            return "[" + String.join(", ", items) + "]";
+        }
+        return "\"" + value.toString().replace("\\", "\\\\").replace("\"", "\\\"") + "\"";
+    }
+
+    /*************************************************************
+     * Exit status for scripts. 2 is a bad command line, 3 art     *
+     * that is missing or too large, 4 any other I/O failure and   *
+     * 1 whatever is left.                                         *
+     *************************************************************/
+
+    public static int exitCode(Throwable error) {
+        if (error == null) {
+            return EXIT_OK;
+        } else if (error instanceof IllegalArgumentException) {
+            return EXIT_USAGE;
+        } else if (error instanceof NoSuchFileException || error instanceof ArtTooLargeException) {
+            return EXIT_NO_ART;
+        } else if (error instanceof IOException) {
+            return EXIT_IO;
+        }
+        return EXIT_FAILURE;
+    }
+
     /********************
     * HEART OF DARKNESS *
     *********************/
@@ -303,45 +4485,195 @@ public class Spacerace {
 
         //Prepare all variables for loading our orbital map
         Spacerace spacerace = new Spacerace();
-        Scanner scanner = new Scanner(System.in);
 
-        Set<Integer> rowsToRemoveSet = spacerace.ParseCmdLine(args);
+        try {
+            spacerace.rowsToRemove = spacerace.ParseCmdLine(args);
+        } catch (IllegalArgumentException e) {
+            System.err.println("Error in arguments: " + e.getMessage());
+            System.exit(exitCode(e));
+        }
+
+        if (spacerace.showVersion) {
+            System.out.println(versionString());
+            return;
+        }
+
+        if (spacerace.bodyName != null || spacerace.viewpointName != null) {
+            try {
+                List<Body> bodies = Body.fromConfig(Files.readAllLines(Paths.get(spacerace.bodiesPath)));
+                if (spacerace.bodyName != null) {
+                    spacerace.body = findBody(bodies, spacerace.bodyName);
+                    spacerace.orbitalPeriodDays = spacerace.body.periodDays;
+                }
+                if (spacerace.viewpointName != null) {
+                    spacerace.viewpoint = findBody(bodies, spacerace.viewpointName);
+                }
+            } catch (IOException | IllegalArgumentException | DateTimeParseException e) {
+                System.err.println("Error loading bodies: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+        }
+
+        if (spacerace.backgroundPath != null) {
+            try {
+                spacerace.backgroundImage = ImageIO.read(Paths.get(spacerace.backgroundPath).toFile());
+                if (spacerace.backgroundImage == null) {
+                    throw new IOException("not an image format Java can read");
+                }
+            } catch (IOException e) {
+                System.err.println("Error loading background: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+        }
+
+        if (spacerace.constellationsPath != null) {
+            try {
+                spacerace.constellations = Constellation.fromConfig(Files.readAllLines(Paths.get(spacerace.constellationsPath)));
+            } catch (IOException | IllegalArgumentException e) {
+                System.err.println("Error loading constellations: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+        }
+
+        if (spacerace.keysPath != null) {
+            try {
+                loadKeyBindings(spacerace.keyBindings, Files.readAllLines(Paths.get(spacerace.keysPath)));
+            } catch (IOException | IllegalArgumentException e) {
+                System.err.println("Error loading key bindings: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+        }
+
+        if (spacerace.showConfig) {
+            System.out.print(spacerace.configToml());
+            return;
+        }
+
+        if (spacerace.scheduleHours > 0) {
+            ZonedDateTime now = ZonedDateTime.now(spacerace.clock);
+            List<BucketChange> changes = spacerace.bucketChangesIn(now, Duration.ofHours(spacerace.scheduleHours));
+            for (BucketChange change : changes) {
+                System.out.println(change.time.format(DateTimeFormatter.ISO_LOCAL_DATE_TIME) + "  " + change.artPath);
+            }
+            if (changes.isEmpty()) {
+                System.out.println("No art changes in the next " + spacerace.scheduleHours + " hours.");
+            }
+            return;
+        }
+
+        if (spacerace.artArchivePath != null) {
+            try {
+                spacerace.openArtArchive(spacerace.artArchivePath);
+            } catch (IOException e) {
+                System.err.println("Error opening art archive: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+        }
 
-        LocalDate date = LocalDate.now();
+        if (spacerace.doctorMode) {
+            List<Check> checks = spacerace.doctor();
+            for (Check check : checks) {
+                System.out.println(check);
+            }
+            int code = doctorExitCode(checks);
+            System.out.println(code == EXIT_OK ? "All critical checks passed." : "Critical checks failed.");
+            System.exit(code);
+        }
 
-        LocalDate winterSolstice = LocalDate.of(date.getYear(), 12, 21);
-        String solsticeAsciiPath = "asciiArt/solstice.txt";
+        if (spacerace.scanMode) {
+            try {
+                List<String> report = spacerace.scanArtDirectory(spacerace.artRoot);
+                for (String line : report) {
+                    System.out.println(line);
+                }
+                System.out.println(report.isEmpty() ? "Art directory and map agree." : report.size() + " issue(s) found.");
+            } catch (IOException e) {
+                System.err.println("Error scanning ASCII art: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+            return;
+        }
 
-        LocalDate vernalEquinox = LocalDate.of(date.getYear(), 3, 20);
-        String vernalAsciiPath = "asciiArt/vernaleq.txt";
+        if (spacerace.replayPath != null) {
+            try {
+                spacerace.replay(spacerace.replayPath);
+            } catch (IOException | InterruptedException e) {
+                System.err.println("Error replaying session: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+            return;
+        }
+        for (String gap : mapGaps(spacerace.degreeArtMap, spacerace.maxGapDegrees)) {
+            System.err.println("Warning: " + gap);
+        }
+        Runtime.getRuntime().addShutdownHook(new Thread(spacerace::shutdown));
+        if (spacerace.csvPath != null) {
+            try {
+                spacerace.startCsv(spacerace.csvPath);
+            } catch (IOException e) {
+                System.err.println("Error opening CSV log: " + e.getMessage());
+            }
+        }
+        if (spacerace.eventLogPath != null) {
+            try {
+                spacerace.startEventLog(spacerace.eventLogPath);
+            } catch (IOException e) {
+                System.err.println("Error opening event log: " + e.getMessage());
+            }
+        }
+        if (spacerace.recordPath != null) {
+            try {
+                spacerace.startRecording(spacerace.recordPath);
+            } catch (IOException e) {
+                System.err.println("Error opening recording: " + e.getMessage());
+            }
+        }
 
-        LocalDate summerSolstice = LocalDate.of(date.getYear(), 6, 21);
-        String summerSolPath = "asciiArt/summersolstice.txt";
+        if (spacerace.gridBodies != null) {
+            try {
+                List<Body> bodies = Body.fromConfig(Files.readAllLines(Paths.get(spacerace.bodiesPath)));
+                int[] size = spacerace.frameSize();
+                MultiClock grid = MultiClock.fromArgs(spacerace, args, bodies, spacerace.gridBodies, size[0], size[1]);
+                grid.run(clampInterval(spacerace.frameMillis, spacerace.minFrameMillis));
+            } catch (IOException | IllegalArgumentException | DateTimeParseException e) {
+                System.err.println("Error building the grid: " + e.getMessage());
+                System.exit(exitCode(e));
+            } catch (InterruptedException e) {
+                Thread.currentThread().interrupt();
+            }
+            return;
+        }
 
-        LocalDate autumnEquinox = LocalDate.of(date.getYear(), 9, 22);
-        String autumnEqPath = "asciiArt/autumneq.txt";
+        if (spacerace.marqueeSpeed > 0 && spacerace.canvasWidth <= 0) {
+            // The marquee needs an edge to scroll against, without --width that is the terminal's
+            spacerace.canvasWidth = terminalSize()[0];
+        }
 
-        double position = calculateEarthPosition(date);
+        LocalDate date = spacerace.currentDate();
 
-        //Special case handling
+        //Special case handling for solstices/equinoxes lives in resolveArtPathForDate
         try {
-            if(date.equals(winterSolstice)) {
-                spacerace.asciiArt(solsticeAsciiPath);
-            } else if(date.equals(vernalEquinox)) {
-                spacerace.asciiArt(vernalAsciiPath);
-            } else if (date.equals(summerSolstice)) {
-                spacerace.asciiArt(summerSolPath);
-            } else if (date.equals(autumnEquinox)) {
-                spacerace.asciiArt(autumnEqPath);
-            }else {
-                spacerace.loadAsciiArtForCurrentPosition(position);
-            }
-            spacerace.modifyArtLines(rowsToRemoveSet);
+            spacerace.loadArtForDate(date);
+            spacerace.currentPosition = spacerace.scenePosition(date);
+            if (spacerace.onceMode) {
+                // A single finished frame, no screen clearing, for scripts and pipes
+                spacerace.writeFrame(spacerace.nextFrame(), "", "");
+                return;
+            }
+            spacerace.fadeIn();
             spacerace.displayArt();
+        } catch (InterruptedException e) {
+            Thread.currentThread().interrupt();
         } catch (IOException e) {
-            System.out.println("Error loading ASCII art: " + e.getMessage());
+            if (spacerace.onceMode) {
+                System.err.println("Error loading ASCII art: " + e.getMessage());
+                System.exit(exitCode(e));
+            }
+            // The placeholder stands in for the missing scene
+            spacerace.currentPosition = spacerace.scenePosition(date);
+            spacerace.displayArt();
         }
         spacerace.startTwinkling();
-        scanner.close();
+        spacerace.startKeyListener();
     }
 }
\ No newline at end of file
@@ -0,0 +1,1422 @@
+import java.awt.image.BufferedImage;
+import java.io.ByteArrayOutputStream;
+import java.io.IOException;
+import java.io.PrintStream;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Files;
+import java.nio.file.NoSuchFileException;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.time.Clock;
+import java.time.Duration;
+import java.time.Instant;
+import java.time.LocalDate;
+import java.time.ZoneOffset;
+import java.time.ZonedDateTime;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.EnumMap;
+import java.util.HashMap;
+import java.util.Iterator;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.NavigableMap;
+import java.util.Random;
+import java.util.TreeMap;
+import java.util.concurrent.Executors;
+import java.util.concurrent.ScheduledExecutorService;
+import java.util.concurrent.ScheduledFuture;
+import java.util.concurrent.TimeUnit;
+import java.util.stream.Collectors;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipOutputStream;
+
+/*****************************************************************************
+ * Plain asserts, no build tool needed. From the repo root:                  *
+ *   javac -encoding UTF-8 -d target Spacerace.java tests/SpaceraceTest.java *
+ *   java -ea -cp target SpaceraceTest                                       *
+ * Art comes from tests/fixtures so nothing depends on asciiArt.             *
+ *****************************************************************************/
+
+public class SpaceraceTest {
+    private static final Path FIXTURES = Paths.get("tests", "fixtures");
+    private static final ZonedDateTime INSTANT = ZonedDateTime.of(2024, 1, 10, 12, 0, 0, 0, ZoneOffset.UTC);
+
+    public static void main(String[] args) throws Exception {
+        boolean assertions = false;
+        assert assertions = true;
+        if (!assertions) {
+            throw new AssertionError("Run with java -ea, the checks are asserts");
+        }
+
+        fixtureLoads();
+        seededTwinkleRepeats();
+        oncePrintsAFrame();
+        bucketCrossingStartsHighlight();
+        scanFindsUnmappedAndMissing();
+        starTintFollowsTheOrbit();
+        issAdvancesAndWraps();
+        heavyWeightDominates();
+        starfieldHonorsCanvasSize();
+        emptyMapFallsBackToStarfield();
+        executorTicksFrames();
+        colorblindPaletteUsesOkabeIto();
+        recordingReplaysInOrder();
+        marqueeWindowsWideLines();
+        oppositeArtPairsAcrossTheOrbit();
+        bufferedFrameKeepsLineOrder();
+        timeMachineOffsetAccumulates();
+        truecolorDowngradesTo256();
+        frameIteratorYieldsFullFrames();
+        bucketKeyWraps();
+        cycleVisitsEveryBucket();
+        seasonEventFiresOncePerDay();
+        gradientBlendsTopToBottom();
+        julianDateAtJ2000();
+        alignmentPlacesArtOnTheCanvas();
+        oversizedArtIsRefused();
+        blendArtPicksBySeed();
+        noColorDropsEscapes();
+        lifetimeKeepsStarCountInBand();
+        offCharReplacesBlanks();
+        styledTextHasOneLinePerRow();
+        sunColumnWrapsAtTheEdge();
+        archiveEntriesAreRead();
+        staticRegionNeverTwinkles();
+        versionNamesTheRelease();
+        smoothedAngleLagsByTheFactor();
+        customPeriodCyclesInFiftyDays();
+        statusBarFillsOnlyEnabledCells();
+        themeCycleWraps();
+        parseAnsiLineSplitsColors();
+        randomSceneFavorsTheWeightedBucket();
+        scheduleListsTheNextChange();
+        tabsExpandToTheNextStop();
+        logoBouncesOffTheWalls();
+        paddingShiftsTheContentArea();
+        mockClockDrivesThePosition();
+        terminalSizeFallsBackTo80x24();
+        trailDecaysThroughTheFadeTable();
+        pinnedAngleHoldsTheScene();
+        exitCodeMatchesFailure();
+        flagValuesMayStartWithDashes();
+        starCountMatchesTheArt();
+        prefetchCachesTheNextBucket();
+        crlfArtLosesCarriageReturns();
+        demoModeTwinklesAFixedSky();
+        idleSlowsTheFrameRate();
+        sidePanelSitsRightOfTheArt();
+        bodiesLoadAndOrbit();
+        newMoonIsBrighterThanFullMoon();
+        asciiOnlyReplacesBoxDrawing();
+        csvLogsARowPerFrame();
+        clampIntervalKeepsTheFloor();
+        phasesKeepStarsOutOfStep();
+        placeholderShowsWhenNoArtLoads();
+        instantHoldsTheAngleAcrossRenders();
+        gridCellsTileEveryBody();
+        brightnessClampsAtTheBounds();
+        doctorFailsOnlyOnCriticalChecks();
+        diffFrameRewritesChangedLines();
+        terminatorFollowsTheHour();
+        quitRebindsToX();
+        colorFadeIsBetweenTheEnds();
+        borderlessUsesTheFullArea();
+        calendarYearCountsLeapDays();
+        constellationOfTheDayShinesBrighter();
+        showConfigReflectsTheInterval();
+        hookRunsOncePerFrame();
+        emptyArtFallsBackToThePlaceholder();
+        artFlagsBuildTheMap();
+        topRowTwinklesMoreOften();
+        mirroredOrderKeepsWordsReadable();
+        flipsKeepBoxesJoined();
+        unchangedFramesAreSkipped();
+        farEpochsAreRejected();
+        shownAngleTogglesToTheBucket();
+        shimmerTracesASine();
+        keyPressesAreLogged();
+        writesAfterShutdownAreDropped();
+        viewpointPicksTheArtFromItsOwnOrbit();
+        fadeInMakesTheConfiguredSteps();
+        raggedArtReportsItsWidestLine();
+        averageFpsOverWindow();
+        rampColorsStarsByBrightness();
+        stateAfterALoadIsConsistent();
+        orbitDialMarksTheOctant();
+        cycleSkipsMissingArt();
+        windShiftsThePhaseDownwind();
+        mapGapsOfTheDefaultMap();
+        mapGapsFindsWideStretches();
+        blackImageIsTheDarkestRampCharacter();
+
+        System.out.println("All tests passed.");
+    }
+
+    /*************************************************************
+     * A Spacerace reading its art from the fixtures and showing  *
+     * the named one, held at a fixed instant with the twinkle    *
+     * seeded so every run rolls the same stars.                  *
+     *************************************************************/
+
+    static Spacerace loadFixture(String name, long seed) throws IOException {
+        Spacerace spacerace = new Spacerace();
+        spacerace.setArtRoot(FIXTURES.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(seed);
+        spacerace.asciiArt(FIXTURES.resolve(name).toString());
+        return spacerace;
+    }
+
+    static void fixtureLoads() throws IOException {
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        assert spacerace.artLines.length == 3 : "fixture is 3 rows";
+        assert spacerace.artLines[1].equals(" * ") : "star in the middle, got '" + spacerace.artLines[1] + "'";
+    }
+
+    static void seededTwinkleRepeats() throws IOException {
+        List<List<String>> first = frames(loadFixture("space010.txt", 42), 300);
+        List<List<String>> second = frames(loadFixture("space010.txt", 42), 300);
+        assert first.stream().allMatch(frame -> frame.size() == 3) : "only the fixture is drawn";
+        assert first.equals(second) : "same seed, same frames";
+        assert first.stream().anyMatch(frame -> !frame.get(1).contains("*")) : "the star twinkles at least once";
+    }
+
+    /*************************************************************
+     * End to end, the class run the way a script would run it,   *
+     * no terminal and nothing on stdin, guarding the argument    *
+     * parsing and the render path together.                      *
+     *************************************************************/
+
+    static void oncePrintsAFrame() throws IOException, InterruptedException {
+        String java = Paths.get(System.getProperty("java.home"), "bin", "java").toString();
+        Process process = new ProcessBuilder(java, "-cp", System.getProperty("java.class.path"), "Spacerace",
+                "--once", "--no-color", "--art-root", FIXTURES.toString(), "--seed", "1")
+                .redirectError(ProcessBuilder.Redirect.DISCARD)
+                .start();
+        process.getOutputStream().close();
+        String output = new String(process.getInputStream().readAllBytes(), StandardCharsets.UTF_8);
+        assert process.waitFor() == 0 : "--once exits cleanly";
+        assert !output.isBlank() : "--once prints the frame";
+        assert output.lines().count() == 3 : "the 3 row fixture, got:\n" + output;
+    }
+
+    static void bucketCrossingStartsHighlight() throws Exception {
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        LocalDate today = INSTANT.toLocalDate();
+        spacerace.loadArtForDate(today);
+        assert !spacerace.checkBucketCrossing(today) : "same bucket, no highlight";
+        LocalDate later = today.plusDays(40);
+        assert !spacerace.resolveScenePath(later).equals(spacerace.resolveScenePath(today));
+        spacerace.setInstant(INSTANT.plusDays(40));
+        assert spacerace.checkBucketCrossing(later) : "a new path starts the highlight";
+        String frame = captureOut(spacerace::renderFrame);
+        assert System.getenv("NO_COLOR") != null || frame.contains("\033[7m") : "the next frame is inverted";
+    }
+
+    interface Action {
+        void run() throws Exception;
+    }
+
+    // Everything printed to stdout while the action runs
+    private static String captureOut(Action action) throws Exception {
+        PrintStream original = System.out;
+        ByteArrayOutputStream buffer = new ByteArrayOutputStream();
+        System.setOut(new PrintStream(buffer, true, StandardCharsets.UTF_8));
+        try {
+            action.run();
+        } finally {
+            System.setOut(original);
+        }
+        return buffer.toString(StandardCharsets.UTF_8);
+    }
+
+    static void scanFindsUnmappedAndMissing() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-scan");
+        Files.writeString(dir.resolve("space010.txt"), " * \n");
+        Files.writeString(dir.resolve("extra.txt"), " * \n");
+        Spacerace spacerace = new Spacerace();
+        spacerace.setArtRoot(dir.toString());
+        List<String> report = spacerace.scanArtDirectory(dir.toString());
+        assert report.contains("Unmapped file: " + dir + "/extra.txt") : report;
+        assert report.stream().noneMatch(line -> line.contains("space010.txt")) : "the mapped file is found";
+        assert report.contains("Missing file for 20 degrees: " + dir.resolve("space2030.txt")) : report;
+    }
+
+    static void starTintFollowsTheOrbit() {
+        int[] perihelion = Spacerace.starTint(3);
+        int[] aphelion = Spacerace.starTint(183);
+        assert !Arrays.equals(perihelion, aphelion) : "warm and cool tints differ";
+        assert perihelion[0] > aphelion[0] && perihelion[2] < aphelion[2] : "warmer is redder, cooler is bluer";
+    }
+
+    static void issAdvancesAndWraps() {
+        Spacerace spacerace = new Spacerace();
+        assert spacerace.advanceIss(20) == 8;
+        assert spacerace.advanceIss(20) == 16;
+        assert spacerace.advanceIss(20) == 4 : "wraps back past the left edge";
+        assert spacerace.advanceIss(0) == 0 : "an empty row parks it";
+    }
+
+    static void heavyWeightDominates() {
+        Random random = new Random(3);
+        int[] counts = new int[3];
+        for (int i = 0; i < 1000; i++) {
+            counts[Spacerace.pickWeighted(new int[]{60, 5, 5}, random)]++;
+        }
+        assert counts[0] > 800 : Arrays.toString(counts);
+        assert counts[1] > 0 && counts[2] > 0 : "light weights still come up";
+    }
+
+    static void starfieldHonorsCanvasSize() {
+        String[] field = configured("--width", "17", "--height", "5").generateStarfield(new Random(1));
+        assert field.length == 5 : field.length;
+        assert Arrays.stream(field).allMatch(line -> line.length() == 17) : "every row is the canvas width";
+    }
+
+    // A Spacerace set up from a command line, with the same parsing main does
+    static Spacerace configured(String... args) {
+        Spacerace spacerace = new Spacerace();
+        spacerace.ParseCmdLine(args);
+        return spacerace;
+    }
+
+    static void emptyMapFallsBackToStarfield() throws IOException {
+        Spacerace spacerace = configured("--no-art", "--width", "12", "--height", "4");
+        spacerace.setInstant(INSTANT);
+        assert spacerace.artBuckets().isEmpty();
+        assert spacerace.resolveScenePath(INSTANT.toLocalDate()) == null;
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        List<String> frame = spacerace.nextFrame();
+        assert frame.size() == 4 && frame.stream().allMatch(line -> line.length() == 12) : frame;
+    }
+
+    static void executorTicksFrames() throws Exception {
+        Spacerace spacerace = configured("--art-root", FIXTURES.toString(), "--interval", "10", "--max-fps", "100");
+        spacerace.setInstant(INSTANT);
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        ScheduledExecutorService executor = Executors.newSingleThreadScheduledExecutor();
+        captureOut(() -> {
+            ScheduledFuture<?> ticking = spacerace.scheduleTwinkling(executor);
+            Thread.sleep(200);
+            ticking.cancel(false);
+        });
+        executor.shutdown();
+        assert executor.awaitTermination(1, TimeUnit.SECONDS);
+        assert spacerace.state().frame >= 3 : "a few ticks ran, got " + spacerace.state().frame;
+    }
+
+    static void colorblindPaletteUsesOkabeIto() {
+        assert configured("--palette", "colorblind").configToml().contains("palette = \"colorblind\"");
+        Spacerace.Palette palette = Spacerace.Palette.COLORBLIND;
+        assert Arrays.equals(palette.starColor('*', 0), new int[]{240, 228, 66}) : "yellow stars";
+        assert Arrays.equals(palette.starColor('+', 0), new int[]{86, 180, 233}) : "sky blue plus stars";
+        assert Arrays.equals(palette.borderColor(), new int[]{204, 121, 167}) : "reddish purple border";
+        assert Arrays.equals(palette.textColor(), new int[]{0, 158, 115}) : "bluish green text";
+        assert Spacerace.Palette.PLAIN.starColor('*', 0) == null : "plain leaves the terminal color";
+    }
+
+    static void recordingReplaysInOrder() throws Exception {
+        Path log = Files.createTempFile("spacerace", ".rec");
+        Spacerace recording = new Spacerace();
+        recording.startRecording(log.toString());
+        recording.recordFrame(List.of("first", "frame"), 1000);
+        recording.recordFrame(List.of("second"), 1005);
+        recording.shutdown();
+        String replayed = captureOut(() -> new Spacerace().replay(log.toString()));
+        String[] lines = replayed.replace("\033[H\033[2J", "").split(System.lineSeparator());
+        assert Arrays.asList(lines).equals(List.of("first", "frame", "second")) : Arrays.toString(lines);
+        Files.writeString(log, "garbage\n");
+        try {
+            new Spacerace().replay(log.toString());
+            assert false : "a bad header is refused";
+        } catch (IOException e) {
+            assert e.getMessage().startsWith("Line 1:") : e.getMessage();
+        }
+        Files.delete(log);
+    }
+
+    static void marqueeWindowsWideLines() {
+        assert Spacerace.windowLine("abcdefgh", 3, 0).equals("abc");
+        assert Spacerace.windowLine("abcdefgh", 3, 4).equals("efg");
+        assert Spacerace.windowLine("abcdefgh", 3, 6).equals("abc") : "starts over past the right edge";
+        assert Spacerace.windowLine("ab", 3, 5).equals("ab") : "lines that fit are left whole";
+    }
+
+    static void oppositeArtPairsAcrossTheOrbit() throws IOException {
+        Spacerace spacerace = new Spacerace();
+        assert spacerace.oppositeArtPath(30).equals(Paths.get("asciiArt", "space200210.txt").toString())
+                : spacerace.oppositeArtPath(30);
+        Spacerace missing = configured("--art-root", FIXTURES.toString(), "--opposite",
+                "--art", "0=space010.txt", "--art", "180=missing.txt");
+        missing.setInstant(INSTANT);
+        assert Spacerace.calculateEarthPosition(INSTANT.toLocalDate()) < 180 : "today is in the 0 bucket";
+        missing.loadArtForDate(INSTANT.toLocalDate());
+        assert Arrays.equals(missing.artLines, new String[]{"   ", " * ", "   "}) : "today's art stays up on its own";
+    }
+
+    static void bufferedFrameKeepsLineOrder() {
+        String n = System.lineSeparator();
+        assert Spacerace.buildFrame(List.of("one", "two", "three")).equals("one" + n + "two" + n + "three" + n);
+        assert Spacerace.buildFrame(List.of()).isEmpty();
+    }
+
+    static void timeMachineOffsetAccumulates() {
+        LocalDate today = LocalDate.of(2024, 1, 31);
+        long offset = Spacerace.shiftOffset(today, 0, true, false);
+        assert offset == 7;
+        offset = Spacerace.shiftOffset(today, offset, true, false);
+        assert offset == 14 : "weeks add up";
+        offset = Spacerace.shiftOffset(today, offset, false, false);
+        assert offset == 7;
+        assert Spacerace.shiftOffset(today, 0, true, true) == 29 : "a month from Jan 31st is Feb 29th in 2024";
+        assert Spacerace.shiftOffset(today, 0, false, true) == -31;
+    }
+
+    static void truecolorDowngradesTo256() {
+        assert Spacerace.rgbTo256(255, 0, 0) == 196;
+        assert Spacerace.rgbTo256(250, 5, 5) == 196 : "nearest cube color";
+        assert Spacerace.rgbTo256(0, 95, 135) == 24;
+        assert Spacerace.rgbTo256(128, 128, 128) == 244 : "greys go to the grey ramp";
+        assert Spacerace.detectColorMode("truecolor", "xterm") == Spacerace.ColorMode.TRUECOLOR;
+        assert Spacerace.detectColorMode(null, "xterm-256color") == Spacerace.ColorMode.ANSI256;
+        assert Spacerace.detectColorMode(null, "vt100") == Spacerace.ColorMode.ANSI16;
+    }
+
+    static void frameIteratorYieldsFullFrames() throws IOException {
+        Spacerace spacerace = loadFixture("space010.txt", 5);
+        Iterator<List<String>> frames = spacerace.frames();
+        for (int i = 0; i < 5; i++) {
+            assert frames.hasNext();
+            assert frames.next().size() == 3 : "every frame has the fixture's 3 lines";
+        }
+    }
+
+    static void bucketKeyWraps() {
+        NavigableMap<Integer, String> map = new Spacerace().artMap();
+        assert Spacerace.bucketKey(map, 355) == 340 : "340 to 360 belongs to the last bucket";
+        assert Spacerace.bucketKey(map, 340) == 340;
+        assert Spacerace.bucketKey(map, 339.9) == 320;
+        assert Spacerace.bucketKey(map, 45.5) == 40;
+        assert Spacerace.bucketKey(map, -5) == 340 : "negative angles wrap";
+        assert Spacerace.bucketKey(map, 365) == 0 : "angles past a full orbit wrap";
+    }
+
+    static void cycleVisitsEveryBucket() {
+        // 500 ms frames and a 1 second dwell, two frames per bucket
+        Spacerace spacerace = configured("--art-root", FIXTURES.toString(), "--cycle", "1", "--interval", "500");
+        List<Integer> buckets = spacerace.artBuckets();
+        List<Integer> visited = new ArrayList<>();
+        for (long frame = 0; frame < buckets.size() * 2L; frame += 2) {
+            assert spacerace.cycleBucket(frame) == spacerace.cycleBucket(frame + 1) : "each bucket dwells two frames";
+            visited.add(spacerace.cycleBucket(frame));
+        }
+        assert visited.equals(buckets) : visited;
+        assert spacerace.cycleBucket(buckets.size() * 2L) == buckets.get(0) : "the last bucket wraps to the first";
+    }
+
+    static void seasonEventFiresOncePerDay() throws Exception {
+        Spacerace spacerace = new Spacerace();
+        LocalDate solstice = LocalDate.of(2024, 6, 21);
+        int[] fired = new int[1];
+        String out = captureOut(() -> {
+            for (LocalDate date : List.of(solstice.minusDays(1), solstice, solstice, solstice, solstice.plusDays(1))) {
+                if (spacerace.checkSeasonEvent(date)) {
+                    fired[0]++;
+                }
+            }
+        });
+        assert fired[0] == 1 : fired[0];
+        assert out.equals("\007") : "one bell for the day";
+    }
+
+    static void gradientBlendsTopToBottom() {
+        int[] top = {0, 0, 8};
+        int[] bottom = {24, 28, 56};
+        assert Arrays.equals(Spacerace.gradientColor(0, 5, top, bottom), top) : "first row is the top color";
+        assert Arrays.equals(Spacerace.gradientColor(2, 5, top, bottom), new int[]{12, 14, 32}) : "middle row is halfway";
+        assert Arrays.equals(Spacerace.gradientColor(4, 5, top, bottom), bottom) : "last row is the bottom color";
+        assert Arrays.equals(Spacerace.gradientColor(0, 1, top, bottom), top) : "a single row stays at the top";
+    }
+
+    static void julianDateAtJ2000() {
+        assert Spacerace.julianDate(ZonedDateTime.of(2000, 1, 1, 12, 0, 0, 0, ZoneOffset.UTC)) == 2451545.0;
+        assert Spacerace.dayOfYear(LocalDate.of(2000, 1, 1)) == 1;
+        assert Spacerace.dayOfYear(LocalDate.of(2000, 2, 29)) == 60 : "leap day";
+        assert Spacerace.dayOfYear(LocalDate.of(2000, 12, 31)) == 366 : "leap year ends on day 366";
+    }
+
+    static void alignmentPlacesArtOnTheCanvas() throws IOException {
+        assert Spacerace.alignedColumn(3, 10, Spacerace.Alignment.RIGHT) == 7;
+        assert Spacerace.alignedColumn(3, 10, Spacerace.Alignment.CENTER) == 3;
+        assert Spacerace.alignedColumn(3, 10, Spacerace.Alignment.LEFT) == 0;
+        for (String[] expected : new String[][]{{"left", "1"}, {"center", "4"}, {"right", "8"}}) {
+            Spacerace spacerace = configured("--align", expected[0], "--width", "10", "--no-color");
+            spacerace.setArtRoot(FIXTURES.toString());
+            spacerace.setInstant(INSTANT);
+            spacerace.setSeed(1);
+            spacerace.asciiArt(FIXTURES.resolve("space010.txt").toString());
+            String row = spacerace.nextFrame().get(1);
+            assert row.length() == 10 : row;
+            assert row.indexOf('*') == Integer.parseInt(expected[1]) : expected[0] + " put the star at " + row.indexOf('*');
+        }
+    }
+
+    static void oversizedArtIsRefused() throws IOException {
+        Spacerace spacerace = configured("--max-art-bytes", "4");
+        try {
+            spacerace.asciiArt(FIXTURES.resolve("space010.txt").toString());
+            assert false : "a fixture over 4 bytes should be refused";
+        } catch (Spacerace.ArtTooLargeException e) {
+            assert e.getMessage().contains("over the 4 byte limit") : e.getMessage();
+        }
+        configured("--max-art-bytes", "64").asciiArt(FIXTURES.resolve("space010.txt").toString());
+    }
+
+    static void blendArtPicksBySeed() {
+        String[] outgoing = {"ab", "cd"};
+        String[] incoming = {"xyz"};
+        assert Arrays.equals(Spacerace.blendArt(outgoing, incoming, 0, new Random(1)), new String[]{"ab ", "cd"});
+        assert Arrays.equals(Spacerace.blendArt(outgoing, incoming, 1, new Random(1)), new String[]{"xyz", "  "});
+        assert Arrays.equals(Spacerace.blendArt(outgoing, incoming, 0.5, new Random(7)),
+                Spacerace.blendArt(outgoing, incoming, 0.5, new Random(7))) : "same seed, same cells";
+        String[] from = new String[20];
+        String[] to = new String[20];
+        Arrays.fill(from, "o".repeat(50));
+        Arrays.fill(to, "i".repeat(50));
+        long incomingCells = Arrays.stream(Spacerace.blendArt(from, to, 0.5, new Random(42)))
+                .flatMapToInt(String::chars).filter(c -> c == 'i').count();
+        assert incomingCells > 400 && incomingCells < 600 : "about half of 1000 cells, got " + incomingCells;
+    }
+
+    static void noColorDropsEscapes() throws IOException {
+        assert Spacerace.noColorUnset(null) && Spacerace.noColorUnset("") : "unset or empty leaves color on";
+        assert !Spacerace.noColorUnset("1");
+        Spacerace spacerace = configured("--no-color", "--gradient", "--tint", "--width", "10");
+        spacerace.setArtRoot(FIXTURES.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(1);
+        spacerace.asciiArt(FIXTURES.resolve("space010.txt").toString());
+        for (List<String> frame : frames(spacerace, 3)) {
+            assert frame.stream().noneMatch(line -> line.contains("\033")) : frame;
+        }
+    }
+
+    static void lifetimeKeepsStarCountInBand() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-lifetime");
+        // 100 stars, so the count has to stay within 11 of that
+        Files.writeString(dir.resolve("space010.txt"), String.join("\n", Collections.nCopies(10, " *".repeat(10))) + "\n");
+        Spacerace spacerace = configured("--lifetime", "5");
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(3);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        for (int frame = 0; frame < 500; frame++) {
+            spacerace.nextFrame();
+            int stars = spacerace.livingStarCount();
+            assert stars >= 89 && stars <= 111 : "frame " + frame + " has " + stars + " stars";
+        }
+    }
+
+    static void offCharReplacesBlanks() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-off");
+        Files.writeString(dir.resolve("space010.txt"), "*".repeat(40) + "\n");
+        Spacerace spacerace = configured("--off-char", "·", "--no-color");
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(5);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        boolean dimmed = false;
+        for (List<String> frame : frames(spacerace, 200)) {
+            String row = frame.get(0);
+            assert row.indexOf(' ') < 0 : "a star went blank: " + row;
+            dimmed |= row.indexOf('·') >= 0;
+        }
+        assert dimmed : "some star should have dimmed to the off char";
+    }
+
+    static void styledTextHasOneLinePerRow() throws IOException {
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        List<List<Spacerace.Span>> text = spacerace.styledText();
+        assert text.size() == 3 : text.size();
+        for (List<Spacerace.Span> line : text) {
+            String joined = line.stream().map(span -> span.text).collect(Collectors.joining());
+            assert joined.length() == 3 : "spans cover the whole row: '" + joined + "'";
+        }
+    }
+
+    static void sunColumnWrapsAtTheEdge() {
+        assert Spacerace.sunColumn(0, 36) == 18 : "the sun is half an orbit from earth";
+        assert Spacerace.sunColumn(90, 36) == 27;
+        assert Spacerace.sunColumn(180, 36) == 0;
+        assert Spacerace.sunColumn(179.9, 36) == 0 : "rounds past the right edge back to the left";
+        assert Spacerace.sunColumn(-90, 36) == 9 : "negative angles wrap";
+        assert Spacerace.sunColumn(45, 0) == 0;
+    }
+
+    static void archiveEntriesAreRead() throws IOException {
+        ByteArrayOutputStream bytes = new ByteArrayOutputStream();
+        try (ZipOutputStream zip = new ZipOutputStream(bytes)) {
+            zip.putNextEntry(new ZipEntry("asciiArt/space010.txt"));
+            zip.write(" * \n***\n".getBytes(StandardCharsets.UTF_8));
+            zip.putNextEntry(new ZipEntry("space2030.txt"));
+            zip.write("+\n".getBytes(StandardCharsets.UTF_8));
+        }
+        Path archive = Files.createTempFile("spacerace", ".zip");
+        Files.write(archive, bytes.toByteArray());
+        Spacerace spacerace = new Spacerace();
+        spacerace.openArtArchive(archive.toString());
+        assert spacerace.readArchiveEntry("asciiArt/space010.txt").equals(List.of(" * ", "***"));
+        assert spacerace.readArchiveEntry("asciiArt/space2030.txt").equals(List.of("+")) : "top level entries match too";
+        try {
+            spacerace.readArchiveEntry("asciiArt/space4050.txt");
+            assert false : "a missing entry should fail";
+        } catch (NoSuchFileException e) {
+            assert e.getMessage().contains("space4050.txt") : e.getMessage();
+        }
+    }
+
+    static void staticRegionNeverTwinkles() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-static");
+        Files.writeString(dir.resolve("space010.txt"), "*".repeat(20) + "\n");
+        // The first five stars are held still, the rest are free to twinkle
+        Spacerace spacerace = configured("--static", "0,0,0,4", "--no-color");
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(2);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        boolean twinkled = false;
+        for (List<String> frame : frames(spacerace, 200)) {
+            String row = frame.get(0);
+            assert row.startsWith("*****") : row;
+            twinkled |= !row.substring(5).equals("*".repeat(15));
+        }
+        assert twinkled : "stars outside the region should still twinkle";
+    }
+
+    static void versionNamesTheRelease() {
+        String version = Spacerace.versionString();
+        assert !version.isEmpty();
+        assert version.startsWith("Spacerace " + Spacerace.VERSION + " (commit ") : version;
+        assert version.contains(", built ") : version;
+    }
+
+    static void smoothedAngleLagsByTheFactor() {
+        assert Math.abs(Spacerace.smoothAngle(0, 100, 0.25) - 25) < 1e-9 : "closes a quarter of the gap";
+        assert Math.abs(Spacerace.smoothAngle(350, 10, 0.5)) < 1e-9 : "goes the short way across 0";
+        assert Spacerace.smoothAngle(40, 80, 1) == 80 : "a factor of 1 follows the raw angle";
+        double shown = 0;
+        for (int frame = 0; frame < 3; frame++) {
+            shown = Spacerace.smoothAngle(shown, 80, 0.5);
+        }
+        assert Math.abs(shown - 70) < 1e-9 : "the gap halves every frame, " + shown;
+    }
+
+    static void customPeriodCyclesInFiftyDays() {
+        LocalDate start = LocalDate.of(2000, 1, 1); // The reference date, angle 0
+        assert Spacerace.calculateEarthPosition(start, 50) == 0;
+        assert Spacerace.calculateEarthPosition(start.plusDays(25), 50) == 180 : "half way after 25 days";
+        assert Spacerace.calculateEarthPosition(start.plusDays(49), 50) > 350;
+        assert Spacerace.calculateEarthPosition(start.plusDays(50), 50) == 0 : "a full orbit in 50 days";
+    }
+
+    static void statusBarFillsOnlyEnabledCells() {
+        Map<Spacerace.StatusSegment, String> values = new EnumMap<>(Spacerace.StatusSegment.class);
+        for (Spacerace.StatusSegment segment : Spacerace.StatusSegment.values()) {
+            values.put(segment, segment.name().toLowerCase());
+        }
+        Map<Spacerace.StatusSegment, Spacerace.Alignment> enabled = new LinkedHashMap<>();
+        enabled.put(Spacerace.StatusSegment.ANGLE, Spacerace.Alignment.LEFT);
+        enabled.put(Spacerace.StatusSegment.DAY, Spacerace.Alignment.LEFT);
+        enabled.put(Spacerace.StatusSegment.DATE, Spacerace.Alignment.RIGHT);
+        String[] cells = Spacerace.statusCells(enabled, values);
+        assert Arrays.equals(cells, new String[]{"angle  day", "", "date"}) : Arrays.toString(cells);
+        assert Arrays.stream(cells).filter(cell -> !cell.isEmpty()).count() == 2;
+        String line = Spacerace.layoutStatus(cells, 20);
+        assert line.equals("angle  day      date") : "'" + line + "'";
+    }
+
+    static void themeCycleWraps() {
+        List<Spacerace.Palette> themes = List.of(Spacerace.Palette.values());
+        Spacerace.Palette last = themes.get(themes.size() - 1);
+        assert Spacerace.nextTheme(themes, themes.get(0)) == themes.get(1);
+        assert Spacerace.nextTheme(themes, last) == themes.get(0) : "the last theme wraps to the first";
+        List<Spacerace.Palette> narrowed = List.of(Spacerace.Palette.COLORBLIND);
+        assert Spacerace.nextTheme(narrowed, Spacerace.Palette.PLAIN) == Spacerace.Palette.COLORBLIND
+                : "a palette outside the list starts it over";
+    }
+
+    static void parseAnsiLineSplitsColors() {
+        List<Spacerace.Span> spans = Spacerace.parseAnsiLine("\033[31mred\033[0m plain\033[38;5;300m");
+        assert spans.size() == 2 : spans.size();
+        assert spans.get(0).text.equals("red") && Arrays.equals(spans.get(0).color, new int[]{205, 0, 0});
+        assert spans.get(1).text.equals(" plain") && spans.get(1).color == null;
+        assert Spacerace.parseAnsiLine("\033[38;2;1;2;3mx").get(0).color[2] == 3;
+        assert Spacerace.parseAnsiLine("\033[38;5;xmx").get(0).color == null : "broken codes are dropped";
+    }
+
+    static void randomSceneFavorsTheWeightedBucket() {
+        // One frame per dwell period so every frame is a fresh pick
+        Spacerace spacerace = configured("--art-root", FIXTURES.toString(), "--random", "1", "--interval", "1000",
+                "--bucket-weights", "90:50", "--seed", "4");
+        Map<Integer, Integer> counts = new HashMap<>();
+        for (long frame = 0; frame < 1000; frame++) {
+            counts.merge(spacerace.cycleBucket(frame), 1, Integer::sum);
+        }
+        int favorite = Collections.max(counts.entrySet(), Map.Entry.comparingByValue()).getKey();
+        assert favorite == 90 : counts;
+        assert counts.get(90) > 500 : "90 carries 50 of the 65 weight, " + counts;
+    }
+
+    static void scheduleListsTheNextChange() {
+        Spacerace spacerace = new Spacerace();
+        // 2000-01-22 is day 21 of the orbit, the first at 20 degrees or more
+        ZonedDateTime from = ZonedDateTime.of(2000, 1, 20, 12, 0, 0, 0, ZoneOffset.UTC);
+        List<Spacerace.BucketChange> changes = spacerace.bucketChangesIn(from, Duration.ofHours(48));
+        assert changes.size() == 1 : changes.size();
+        assert changes.get(0).time.equals(ZonedDateTime.of(2000, 1, 22, 0, 0, 0, 0, ZoneOffset.UTC)) : changes.get(0).time;
+        assert changes.get(0).artPath.endsWith("space2030.txt") : changes.get(0).artPath;
+        assert spacerace.bucketChangesIn(from, Duration.ofHours(12)).isEmpty() : "no boundary in half a day";
+    }
+
+    static void tabsExpandToTheNextStop() throws IOException {
+        assert Spacerace.expandTabs("ab\tc", 4).equals("ab  c");
+        assert Spacerace.expandTabs("\t*", 8).equals("        *");
+        assert Spacerace.expandTabs("abcd\t*", 4).equals("abcd    *") : "a tab on a stop moves a full width";
+        Path dir = Files.createTempDirectory("spacerace-tabs");
+        Files.writeString(dir.resolve("space010.txt"), "*\t*  \t\n");
+        Spacerace spacerace = configured("--tab-width", "4", "--trim");
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        assert Arrays.equals(spacerace.artLines, new String[]{"*   *"}) : Arrays.toString(spacerace.artLines);
+    }
+
+    static void logoBouncesOffTheWalls() {
+        assert Arrays.equals(Spacerace.bounce(3, 1, 10), new int[]{4, 1}) : "moves freely inside";
+        assert Arrays.equals(Spacerace.bounce(10, 1, 10), new int[]{9, -1}) : "turns around at the far wall";
+        assert Arrays.equals(Spacerace.bounce(0, -1, 10), new int[]{1, 1}) : "turns around at the near wall";
+        assert Arrays.equals(Spacerace.bounce(0, 1, 0), new int[]{0, -1}) : "stays put with no room to move";
+    }
+
+    static void paddingShiftsTheContentArea() throws IOException {
+        assert Arrays.equals(Spacerace.contentArea(24, 2, 3), new int[]{2, 19});
+        assert Arrays.equals(Spacerace.contentArea(5, 4, 4), new int[]{4, 0}) : "padding can leave no rows";
+        assert Arrays.equals(Spacerace.contentArea(3, 5, 0), new int[]{3, 0}) : "the top never runs past the frame";
+        Spacerace spacerace = configured("--padding", "2,1", "--no-color");
+        spacerace.setArtRoot(FIXTURES.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(1);
+        spacerace.asciiArt(FIXTURES.resolve("space010.txt").toString());
+        List<String> frame = spacerace.nextFrame();
+        assert frame.size() == 6 : frame;
+        assert frame.get(3).contains("*") : "the star row moves down by the top padding, " + frame;
+    }
+
+    static void mockClockDrivesThePosition() {
+        Spacerace spacerace = new Spacerace();
+        spacerace.setClock(Clock.fixed(Instant.parse("2000-01-01T12:00:00Z"), ZoneOffset.UTC));
+        assert spacerace.currentDate().equals(LocalDate.of(2000, 1, 1));
+        assert spacerace.orbitPosition(spacerace.currentDate()) == 0 : "the reference date is angle 0";
+        spacerace.setClock(Clock.fixed(Instant.parse("2000-07-01T12:00:00Z"), ZoneOffset.UTC));
+        double position = spacerace.orbitPosition(spacerace.currentDate());
+        assert Math.abs(position - 182 * 360 / 365.25) < 1e-9 : position;
+    }
+
+    static void terminalSizeFallsBackTo80x24() {
+        assert Arrays.equals(Spacerace.resolveTerminalSize("40 120\n"), new int[]{120, 40}) : "stty prints rows first";
+        assert Arrays.equals(Spacerace.resolveTerminalSize(null), new int[]{80, 24}) : "no tty to ask";
+        assert Arrays.equals(Spacerace.resolveTerminalSize("stty: not a tty"), new int[]{80, 24});
+        assert Arrays.equals(Spacerace.resolveTerminalSize("0 0"), new int[]{80, 24}) : "a zero size is no size";
+    }
+
+    static void trailDecaysThroughTheFadeTable() throws IOException {
+        Spacerace spacerace = configured("--trail", "4", "--no-color");
+        spacerace.setArtRoot(FIXTURES.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(1);
+        spacerace.asciiArt(FIXTURES.resolve("space010.txt").toString());
+        StringBuilder star = new StringBuilder();
+        for (List<String> frame : frames(spacerace, 100)) {
+            star.append(frame.get(1).charAt(1));
+        }
+        int start = star.indexOf("+");
+        assert start > 0 : "the star never twinkled: " + star;
+        assert star.substring(start, start + 4).equals("+. *") : "decays a step a frame, got " + star;
+    }
+
+    static void pinnedAngleHoldsTheScene() {
+        assert Spacerace.parseAngle(" 95 ") == 95.0;
+        assert Spacerace.parseAngle("north") == null && Spacerace.parseAngle("") == null && Spacerace.parseAngle(null) == null;
+        assert Spacerace.parseAngle("NaN") == null : "only finite angles pin";
+        Spacerace spacerace = new Spacerace();
+        spacerace.setPinnedAngle("95");
+        for (LocalDate date : List.of(LocalDate.of(2024, 1, 10), LocalDate.of(2024, 8, 1))) {
+            assert spacerace.scenePosition(date) == 95 : date;
+            assert spacerace.resolveScenePath(date).endsWith("space90110.txt") : spacerace.resolveScenePath(date);
+        }
+        spacerace.setPinnedAngle("north");
+        assert spacerace.scenePosition(LocalDate.of(2024, 1, 10)) < 20 : "an invalid value follows the date again";
+    }
+
+    static void exitCodeMatchesFailure() {
+        assert Spacerace.exitCode(null) == 0;
+        assert Spacerace.exitCode(new IllegalArgumentException()) == 2;
+        assert Spacerace.exitCode(new NoSuchFileException("a.txt")) == 3;
+        assert Spacerace.exitCode(new Spacerace.ArtTooLargeException("a.txt", 10, 5)) == 3;
+        assert Spacerace.exitCode(new IOException()) == 4;
+        assert Spacerace.exitCode(new IllegalStateException()) == 1;
+    }
+
+    static void flagValuesMayStartWithDashes() {
+        Spacerace spacerace = configured("--placeholder", "--no scene--", "--seed", "3");
+        assert spacerace.configToml().contains("placeholder = \"--no scene--\"") : spacerace.configToml();
+        try {
+            configured("--placeholder", "x", "--bogus");
+            assert false : "an unknown flag after a value should still be rejected";
+        } catch (IllegalArgumentException e) {
+            assert e.getMessage().endsWith("--bogus") : e.getMessage();
+        }
+    }
+
+    static void starCountMatchesTheArt() throws IOException {
+        assert loadFixture("space010.txt", 1).starCount() == 1 : "the fixture has one star";
+        Path dir = Files.createTempDirectory("spacerace-stars");
+        Files.writeString(dir.resolve("stars.txt"), "* ┼ .\n+ & *\n");
+        Spacerace spacerace = new Spacerace();
+        spacerace.asciiArt(dir.resolve("stars.txt").toString());
+        assert spacerace.starCount() == 4 : "dots and markers don't twinkle, " + spacerace.starCount();
+        assert new Spacerace().starCount() == 0 : "nothing loaded yet";
+    }
+
+    static void prefetchCachesTheNextBucket() throws Exception {
+        Path dir = Files.createTempDirectory("spacerace-prefetch");
+        Files.writeString(dir.resolve("space010.txt"), " * \n");
+        Files.writeString(dir.resolve("space2030.txt"), "***\n");
+        Spacerace spacerace = new Spacerace();
+        spacerace.setArtRoot(dir.toString());
+        String next = dir.resolve("space2030.txt").toString();
+        spacerace.prefetchNextBucket(LocalDate.of(2000, 1, 2));
+        assert !spacerace.isPrefetched(next) : "too far from the boundary to read ahead";
+        // 2000-01-21 sits at 19.7 degrees, inside the margin before the 20 bucket
+        spacerace.prefetchNextBucket(LocalDate.of(2000, 1, 21));
+        for (int wait = 0; wait < 200 && !spacerace.isPrefetched(next); wait++) {
+            Thread.sleep(10);
+        }
+        assert spacerace.isPrefetched(next) : "the 20 bucket should be read ahead";
+        Files.delete(dir.resolve("space2030.txt"));
+        spacerace.asciiArt(next);
+        assert Arrays.equals(spacerace.artLines, new String[]{"***"}) : "the crossing loads from the cache";
+        assert !spacerace.isPrefetched(next) : "taken out of the cache once used";
+    }
+
+    static void crlfArtLosesCarriageReturns() throws IOException {
+        assert Spacerace.normalizeLines("a\r\nb\r\n").equals(List.of("a", "b"));
+        assert Spacerace.normalizeLines("a\rb\n\n").equals(List.of("ab", "")) : "stray \\r is dropped, blank rows kept";
+        Path dir = Files.createTempDirectory("spacerace-crlf");
+        Files.writeString(dir.resolve("space010.txt"), " *\t*\r\n**\r\r\n");
+        Spacerace spacerace = configured("--tab-width", "4");
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        assert Arrays.stream(spacerace.artLines).noneMatch(line -> line.indexOf('\r') >= 0) : "no \\r left";
+        assert Arrays.equals(spacerace.artLines, new String[]{" *  *", "**"}) : Arrays.toString(spacerace.artLines);
+    }
+
+    static void demoModeTwinklesAFixedSky() throws IOException {
+        List<String[]> skies = new ArrayList<>();
+        for (LocalDate date : List.of(LocalDate.of(2024, 1, 10), LocalDate.of(2024, 6, 21))) {
+            Spacerace spacerace = configured("--demo", "--width", "12", "--height", "4", "--no-color");
+            spacerace.setInstant(INSTANT);
+            assert spacerace.resolveScenePath(date) == null : "no art file in demo mode";
+            spacerace.loadArtForDate(date);
+            skies.add(spacerace.artLines.clone());
+            for (List<String> frame : frames(spacerace, 3)) {
+                assert frame.size() == 4 && frame.stream().allMatch(line -> line.length() == 12) : frame;
+            }
+        }
+        assert Arrays.equals(skies.get(0), skies.get(1)) : "the same sky whatever the date";
+    }
+
+    static void idleSlowsTheFrameRate() {
+        Instant now = Instant.parse("2024-01-10T12:00:00Z");
+        Duration idle = Duration.ofSeconds(60);
+        assert Spacerace.frameInterval(1000, now.minusSeconds(5), now, idle) == 1000 : "recent input keeps the rate";
+        assert Spacerace.frameInterval(1000, now.minusSeconds(60), now, idle) == 4000 : "idle slows to a quarter";
+        assert Spacerace.frameInterval(1000, now.minusSeconds(600), now, null) == 1000 : "no --idle, no slowdown";
+    }
+
+    static void sidePanelSitsRightOfTheArt() {
+        List<String> art = List.of("ab", "abcd", "a", "abc");
+        List<String> panel = Spacerace.boxPanel(List.of("x", "yy"));
+        assert panel.equals(List.of("┌────┐", "│ x  │", "│ yy │", "└────┘")) : panel;
+        List<String> combined = Spacerace.sideBySide(art, panel, 2);
+        assert combined.size() == 4;
+        for (int row = 0; row < combined.size(); row++) {
+            // The art keeps columns 0-3, the panel starts after the gap at 6
+            assert combined.get(row).startsWith(art.get(row)) : combined.get(row);
+            assert combined.get(row).indexOf(panel.get(row)) == 6 : combined.get(row);
+        }
+    }
+
+    static void bodiesLoadAndOrbit() {
+        List<Spacerace.Body> bodies = Spacerace.Body.fromConfig(List.of(
+                "# name period eccentricity epoch", "", "earth 365.25 0 2000-01-01", "mars 686 0.0934 2000-01-01"));
+        assert bodies.size() == 2 : bodies.size();
+        Spacerace.Body earth = Spacerace.findBody(bodies, "Earth");
+        Spacerace.Body mars = Spacerace.findBody(bodies, "mars");
+        assert Math.abs(earth.position(LocalDate.of(2000, 4, 1)) - 91 * 360 / 365.25) < 1e-9 : "a circle moves evenly";
+        assert mars.position(mars.epoch) == 0;
+        assert Math.abs(mars.position(mars.epoch.plusDays(343)) - 180) < 1e-9 : "aphelion at half the period";
+        assert mars.position(mars.epoch.plusDays(100)) > 100 * 360 / 686.0 : "faster than average near perihelion";
+    }
+
+    static void newMoonIsBrighterThanFullMoon() {
+        ZonedDateTime newMoon = ZonedDateTime.of(2024, 1, 11, 12, 0, 0, 0, ZoneOffset.UTC);
+        ZonedDateTime fullMoon = ZonedDateTime.of(2024, 1, 25, 18, 0, 0, 0, ZoneOffset.UTC);
+        assert Spacerace.moonBrightness(newMoon) > 0.95 : Spacerace.moonBrightness(newMoon);
+        assert Spacerace.moonBrightness(fullMoon) < 0.05 : Spacerace.moonBrightness(fullMoon);
+        assert Math.abs(Spacerace.moonPhase(fullMoon) - 0.5) < 0.02 : Spacerace.moonPhase(fullMoon);
+    }
+
+    static void asciiOnlyReplacesBoxDrawing() throws IOException {
+        assert Spacerace.toAscii("┌─┼─┐│x").equals("+-+-+|x");
+        StringBuilder block = new StringBuilder();
+        for (char c = '─'; c <= '╿'; c++) {
+            block.append(c);
+        }
+        assert Spacerace.toAscii(block.toString()).chars().allMatch(c -> c == '+' || c == '|' || c == '-');
+        Path dir = Files.createTempDirectory("spacerace-ascii");
+        Files.writeString(dir.resolve("space010.txt"), "┼ ┼ ┼ ┼ ┼ ┼\n├──*──┤\n");
+        Spacerace spacerace = configured("--ascii-only", "--no-color");
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(6);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        for (List<String> frame : frames(spacerace, 100)) {
+            assert frame.stream().flatMapToInt(String::chars).noneMatch(c -> c >= '─' && c <= '╿') : frame;
+        }
+    }
+
+    static void csvLogsARowPerFrame() throws IOException {
+        Path csv = Files.createTempFile("spacerace", ".csv");
+        Files.delete(csv);
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        spacerace.startCsv(csv.toString());
+        frames(spacerace, 3);
+        spacerace.shutdown();
+        List<String> rows = Files.readAllLines(csv);
+        assert rows.size() == 4 : rows;
+        assert rows.get(0).equals("timestamp,angle,season,art_file");
+        String angle = String.format("%.4f", Spacerace.calculateEarthPosition(INSTANT.toLocalDate()));
+        for (String row : rows.subList(1, 4)) {
+            assert row.equals("2024-01-10T12:00:00Z," + angle + ",Winter," + FIXTURES.resolve("space010.txt")) : row;
+        }
+        assert Spacerace.csvRow(INSTANT, 1.5, "Spring", "a,b.txt").endsWith(",\"a,b.txt\"") : "commas are quoted";
+    }
+
+    static void clampIntervalKeepsTheFloor() {
+        assert Spacerace.clampInterval(0, 16) == 16;
+        assert Spacerace.clampInterval(100, 16) == 100;
+    }
+
+    static void phasesKeepStarsOutOfStep() {
+        char[] fade = {'*', '+', '.', ' '};
+        assert Spacerace.phaseGlyph(fade, 0, 0, 20) == '*' : "phase 0 starts at full brightness";
+        assert Spacerace.phaseGlyph(fade, 0.5, 0, 20) == ' ' : "half a period on is at its dimmest";
+        assert Spacerace.phaseGlyph(fade, 0, 10, 20) == Spacerace.phaseGlyph(fade, 0.5, 0, 20) : "frames shift the wave";
+    }
+
+    static void placeholderShowsWhenNoArtLoads() throws IOException {
+        Path empty = Files.createTempDirectory("spacerace-empty");
+        Spacerace spacerace = configured("--art-root", empty.toString(), "--width", "24", "--height", "3",
+                "--placeholder", "no scene {angle}");
+        spacerace.setInstant(INSTANT);
+        try {
+            spacerace.loadArtForDate(INSTANT.toLocalDate());
+            assert false : "the missing file is still reported";
+        } catch (NoSuchFileException e) {
+            // Expected
+        }
+        // INSTANT is just under 9 degrees into the orbit
+        assert Arrays.equals(spacerace.artLines, new String[]{" ".repeat(24), "      no scene 009      ", " ".repeat(24)})
+                : Arrays.toString(spacerace.artLines);
+    }
+
+    static void instantHoldsTheAngleAcrossRenders() throws IOException {
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        double expected = Spacerace.calculateEarthPosition(INSTANT.toLocalDate());
+        for (int render = 0; render < 3; render++) {
+            spacerace.nextFrame();
+            assert spacerace.state().angle == expected : spacerace.state().angle;
+        }
+        spacerace.clearInstant();
+        assert spacerace.currentDate().equals(LocalDate.now()) : "back on real time";
+    }
+
+    static void gridCellsTileEveryBody() {
+        for (int count = 1; count <= 5; count++) {
+            int[][] cells = Spacerace.MultiClock.gridCells(count, 80, 24);
+            assert cells.length == count : count + " bodies gave " + cells.length + " cells";
+        }
+        // Three bodies on two columns, with a gap between them and a label row above each tile
+        int[][] cells = Spacerace.MultiClock.gridCells(3, 82, 24);
+        assert Arrays.equals(cells[0], new int[]{0, 0, 40, 11}) : Arrays.toString(cells[0]);
+        assert Arrays.equals(cells[1], new int[]{42, 0, 40, 11}) : Arrays.toString(cells[1]);
+        assert Arrays.equals(cells[2], new int[]{0, 12, 40, 11}) : Arrays.toString(cells[2]);
+    }
+
+    static void brightnessClampsAtTheBounds() throws Exception {
+        assert Spacerace.clampBrightness(3) == 2.0;
+        assert Spacerace.clampBrightness(0.1) == 0.5;
+        assert Spacerace.clampBrightness(1.3) == 1.3;
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        // Every key press redraws with the new brightness on top
+        String up = captureOut(() -> {
+            for (int press = 0; press < 20; press++) {
+                spacerace.handleKey("+");
+            }
+        });
+        assert up.contains("Brightness: 2.0x") && !up.contains("Brightness: 2.1x") : "stops at twice the brightness";
+        String down = captureOut(() -> {
+            for (int press = 0; press < 20; press++) {
+                spacerace.handleKey("-");
+            }
+        });
+        assert down.contains("Brightness: 0.5x") && !down.contains("Brightness: 0.4x") : "stops at half the brightness";
+    }
+
+    static void doctorFailsOnlyOnCriticalChecks() {
+        Spacerace.Check warning = new Spacerace.Check("terminal", false, false, "not a terminal");
+        Spacerace.Check passed = new Spacerace.Check("art map", true, true, "16 entries");
+        Spacerace.Check failed = new Spacerace.Check("scene", false, true, "can't load");
+        assert Spacerace.doctorExitCode(List.of()) == 0;
+        assert Spacerace.doctorExitCode(List.of(warning, passed)) == 0 : "warnings don't fail the run";
+        assert Spacerace.doctorExitCode(List.of(passed, failed, warning)) == 1 : "a critical failure does";
+    }
+
+    static void diffFrameRewritesChangedLines() {
+        List<String> updates = Spacerace.diffFrame(List.of("a", "b"), List.of("a", "c", "d"));
+        assert updates.equals(List.of("\033[2;1Hc\033[K", "\033[3;1Hd\033[K")) : updates;
+        assert Spacerace.diffFrame(List.of("a", "b"), List.of("a")).equals(List.of("\033[2;1H\033[K"))
+                : "lost lines are wiped";
+        assert Spacerace.diffFrame(List.of("a"), List.of("a")).isEmpty();
+        assert Spacerace.diffFrame(List.of("a", "b", "c"), List.of("a", "x", "c")).size() == 1 : "one change, one update";
+    }
+
+    static void terminatorFollowsTheHour() {
+        assert Spacerace.subsolarLongitude(12) == 0 : "the sun is overhead at noon";
+        assert Spacerace.subsolarLongitude(0) == 180 : "and on the far side at midnight";
+        assert Math.abs(Spacerace.daylightHalfWidth(45, 78) - 90) < 1e-9 : "half the world is lit on the equinox";
+        assert Spacerace.isSunlit(0, 0, 12, 78) && !Spacerace.isSunlit(0, 0, 0, 78);
+        assert Spacerace.isSunlit(180, 0, 0, 78) : "the antipode has its noon at our midnight";
+        assert Spacerace.isSunlit(0, 70, 0, 170) : "the arctic summer keeps the sun up at midnight";
+    }
+
+    static void quitRebindsToX() {
+        Map<String, Spacerace.Action> bindings = Spacerace.defaultKeyBindings();
+        assert bindings.get("q") == Spacerace.Action.QUIT;
+        Spacerace.loadKeyBindings(bindings, List.of("# vim fingers", "quit x", "forward-week l"));
+        assert bindings.get("x") == Spacerace.Action.QUIT : "x quits now";
+        assert !bindings.containsKey("q") : "and q is freed up";
+        assert bindings.get("l") == Spacerace.Action.FORWARD_WEEK;
+        assert bindings.get("p") == Spacerace.Action.PAUSE : "actions left out keep their defaults";
+        try {
+            Spacerace.loadKeyBindings(bindings, List.of("quit ctrl-x"));
+            assert false : "an unknown key name should be rejected";
+        } catch (IllegalArgumentException e) {
+            assert e.getMessage().startsWith("Unknown key ctrl-x") : e.getMessage();
+        }
+    }
+
+    static void colorFadeIsBetweenTheEnds() {
+        int[] from = {0, 100, 200};
+        int[] to = {200, 100, 0};
+        int[] midway = Spacerace.lerpColor(from, to, 0.5);
+        assert Arrays.equals(midway, new int[]{100, 100, 100}) : Arrays.toString(midway);
+        int[] early = Spacerace.lerpColor(from, to, 0.25);
+        for (int i = 0; i < 3; i++) {
+            assert early[i] >= Math.min(from[i], to[i]) && early[i] <= Math.max(from[i], to[i]) : Arrays.toString(early);
+        }
+        assert Arrays.equals(Spacerace.lerpColor(null, to, 0), new int[]{192, 192, 192}) : "no color fades from the default";
+        assert Spacerace.lerpColor(null, null, 0.5) == null;
+    }
+
+    static void borderlessUsesTheFullArea() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-borderless");
+        Files.writeString(dir.resolve("space010.txt"), "┌──────┐\n│* ┌┐ *│\n│  └┘  │\n└──────┘\n");
+        Spacerace spacerace = configured("--borderless", "--art-root", dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        assert Arrays.equals(spacerace.artLines, new String[]{"* ┌┐ *", "  └┘  "}) : Arrays.toString(spacerace.artLines);
+        Spacerace framed = configured("--art-root", dir.toString());
+        framed.setInstant(INSTANT);
+        framed.loadArtForDate(INSTANT.toLocalDate());
+        assert framed.artLines.length == 4 && framed.artLines[0].length() == 8 : "the frame stays without the flag";
+    }
+
+    static void calendarYearCountsLeapDays() {
+        // Day 100 of each year, April 9th in 2024 and April 10th in 2023
+        double leap = Spacerace.calendarYearPosition(LocalDate.of(2024, 4, 9));
+        double common = Spacerace.calendarYearPosition(LocalDate.of(2023, 4, 10));
+        assert Math.abs(leap - 99 * 360.0 / 366) < 1e-9 : leap;
+        assert Math.abs(common - 99 * 360.0 / 365) < 1e-9 : common;
+        assert leap < common : "a longer year moves a little less per day";
+        assert Spacerace.calendarYearPosition(LocalDate.of(2024, 1, 1)) == 0 : "January 1st is always 0";
+        Spacerace spacerace = configured("--calendar-year");
+        assert spacerace.orbitPosition(LocalDate.of(2024, 4, 9)) == leap : "the flag switches the orbit over";
+    }
+
+    static void constellationOfTheDayShinesBrighter() {
+        List<Spacerace.Constellation> constellations = Spacerace.Constellation.fromConfig(List.of(
+                "space010.txt orion 1,1 2,3", "space010.txt lyra 0,4", "space2030.txt cygnus 3,3"));
+        LocalDate day = LocalDate.of(2024, 1, 10);
+        Spacerace.Constellation today = Spacerace.constellationOfTheDay(constellations, "asciiArt/space010.txt", day);
+        Spacerace.Constellation tomorrow = Spacerace.constellationOfTheDay(constellations, "asciiArt/space010.txt",
+                day.plusDays(1));
+        assert today != tomorrow && today.artFile.equals("space010.txt") && tomorrow.artFile.equals("space010.txt")
+                : "a different group of this art each day";
+        assert Spacerace.constellationOfTheDay(constellations, "asciiArt/space4050.txt", day) == null;
+        Spacerace.Constellation orion = constellations.get(0);
+        assert orion.contains(2, 3) && !orion.contains(3, 2);
+        // Members twinkle at the usual odds divided by the pulse, never more often than the rest
+        for (long frame = 0; frame < 20; frame++) {
+            assert Spacerace.constellationPulse(frame) >= 1 : frame;
+        }
+        assert Spacerace.constellationPulse(10) == 4 : "peaks at CONSTELLATION_BOOST over the rest";
+    }
+
+    static void showConfigReflectsTheInterval() {
+        assert configured().configToml().contains("frame_millis = 1000\n") : "the default interval";
+        String toml = configured("--interval", "250", "--show-config").configToml();
+        assert toml.contains("frame_millis = 250\n") : toml;
+    }
+
+    static void hookRunsOncePerFrame() throws Exception {
+        Spacerace spacerace = configured("--art-root", FIXTURES.toString(), "--interval", "1", "--max-fps", "1000");
+        spacerace.setInstant(INSTANT);
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        List<Long> frames = new ArrayList<>();
+        captureOut(() -> {
+            try {
+                spacerace.runWithHook(state -> {
+                    frames.add(state.frame);
+                    if (frames.size() == 5) {
+                        // The loop runs forever, a throwing hook is the way out
+                        throw new IllegalStateException("enough frames");
+                    }
+                });
+            } catch (IllegalStateException e) {
+                assert e.getMessage().equals("enough frames");
+            }
+        });
+        assert frames.size() == 5 : frames;
+        for (int i = 1; i < frames.size(); i++) {
+            assert frames.get(i) == frames.get(i - 1) + 1 : "one call per frame, " + frames;
+        }
+    }
+
+    static void emptyArtFallsBackToThePlaceholder() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-emptyart");
+        Files.writeString(dir.resolve("space010.txt"), "");
+        Spacerace spacerace = configured("--art-root", dir.toString(), "--width", "20", "--height", "3",
+                "--placeholder", "empty {angle}");
+        spacerace.setInstant(INSTANT);
+        PrintStream original = System.err;
+        ByteArrayOutputStream warning = new ByteArrayOutputStream();
+        System.setErr(new PrintStream(warning, true, StandardCharsets.UTF_8));
+        try {
+            spacerace.loadArtForDate(INSTANT.toLocalDate());
+        } finally {
+            System.setErr(original);
+        }
+        assert warning.toString(StandardCharsets.UTF_8).contains(dir.resolve("space010.txt") + " is empty") : warning;
+        assert spacerace.artLines.length == 3 && spacerace.artLines[1].contains("empty 009")
+                : Arrays.toString(spacerace.artLines);
+    }
+
+    static void artFlagsBuildTheMap() {
+        Spacerace spacerace = configured("--art", "0=a.txt", "--art", " 40 = b.txt", "--art", "200=c/d=e.txt");
+        NavigableMap<Integer, String> expected = new TreeMap<>(Map.of(0, "a.txt", 40, "b.txt", 200, "c/d=e.txt"));
+        assert spacerace.artMap().equals(expected) : "the flags replace the default map, " + spacerace.artMap();
+        for (String bad : new String[]{"40", "40=", "forty=b.txt", "360=b.txt", "-5=b.txt"}) {
+            try {
+                Spacerace.putArtMapping(new TreeMap<>(), bad);
+                assert false : bad + " should be rejected";
+            } catch (IllegalArgumentException e) {
+                assert e.getMessage().endsWith(bad) : e.getMessage();
+            }
+        }
+    }
+
+    static void topRowTwinklesMoreOften() throws IOException {
+        assert Spacerace.rowIntensity(0, 5, 2, 0.5) == 2 && Spacerace.rowIntensity(4, 5, 2, 0.5) == 0.5;
+        assert Spacerace.rowIntensity(2, 5, 2, 0.5) == 1.25 : "a straight line in between";
+        Path dir = Files.createTempDirectory("spacerace-gradient");
+        Files.writeString(dir.resolve("space010.txt"), "*".repeat(30) + "\n" + "*".repeat(30) + "\n");
+        Spacerace spacerace = configured("--twinkle-gradient", "2:0.2", "--no-color");
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(8);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        long[] changed = new long[2];
+        for (List<String> frame : frames(spacerace, 200)) {
+            for (int row = 0; row < 2; row++) {
+                changed[row] += frame.get(row).chars().filter(c -> c != '*').count();
+            }
+        }
+        assert changed[0] > changed[1] * 2 : "top " + changed[0] + ", bottom " + changed[1];
+    }
+
+    static void mirroredOrderKeepsWordsReadable() {
+        assert Arrays.equals(Spacerace.mirroredOrder("*. "), new int[]{3, 2, 1});
+        assert Arrays.equals(Spacerace.mirroredOrder("ab  *"), new int[]{5, -1, -2, -3, -4})
+                : Arrays.toString(Spacerace.mirroredOrder("ab  *"));
+    }
+
+    static void flipsKeepBoxesJoined() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace-flip");
+        Files.writeString(dir.resolve("space010.txt"), "*  ┌\n ┌┐ \n");
+        Spacerace vertical = configured("--flip-v", "--art-root", dir.toString());
+        vertical.setInstant(INSTANT);
+        vertical.loadArtForDate(INSTANT.toLocalDate());
+        assert Arrays.equals(vertical.artLines, new String[]{" └┘ ", "*  └"}) : Arrays.toString(vertical.artLines);
+        Spacerace horizontal = configured("--flip-h", "--art-root", dir.toString());
+        horizontal.setInstant(INSTANT);
+        horizontal.loadArtForDate(INSTANT.toLocalDate());
+        assert Arrays.equals(horizontal.artLines, new String[]{"┐  *", " ┌┐ "}) : Arrays.toString(horizontal.artLines);
+    }
+
+    static void unchangedFramesAreSkipped() throws Exception {
+        Path dir = Files.createTempDirectory("spacerace-skip");
+        // No stars, so nothing twinkles and every frame is the same
+        Files.writeString(dir.resolve("space010.txt"), "   \n . \n");
+        Spacerace spacerace = configured("--skip-unchanged", "--art-root", dir.toString(), "--no-color");
+        spacerace.setInstant(INSTANT);
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        String out = captureOut(() -> {
+            for (int frame = 0; frame < 4; frame++) {
+                spacerace.renderFrame();
+            }
+        });
+        assert out.split("\033\\[H\033\\[2J", -1).length - 1 == 1 : "only the first frame is written, got " + out;
+    }
+
+    static void farEpochsAreRejected() {
+        assert Spacerace.Body.checkEpoch(LocalDate.of(-7000, 1, 1)).getYear() == -7000 : "within 10000 years is fine";
+        try {
+            Spacerace.Body.fromConfig(List.of("mars 686.98 0.0934 -20000-01-01"));
+            assert false : "an epoch 22000 years back should be rejected";
+        } catch (IllegalArgumentException e) {
+            assert e.getMessage().startsWith("Epoch -20000-01-01 is more than 10000 years") : e.getMessage();
+        }
+        double angle = new Spacerace.Body("far", 365.25, 0, LocalDate.of(-7000, 1, 1)).position(LocalDate.of(2024, 1, 10));
+        assert Double.isFinite(angle) && angle >= 0 && angle < 360 : angle;
+    }
+
+    static void shownAngleTogglesToTheBucket() {
+        NavigableMap<Integer, String> map = new Spacerace().artMap();
+        assert Spacerace.shownAngle(137.4, 137.4, map, false) == 137.4 : "precise by default";
+        assert Spacerace.shownAngle(137.4, 137.4, map, true) == 120 : "b shows the bucket";
+        assert Spacerace.shownAngle(137.4, 355, map, true) == 340 : "the bucket of the angle that picked the art";
+        assert Spacerace.shownAngle(137.4, 137.4, new TreeMap<>(), true) == 137.4 : "no map, no bucket";
+    }
+
+    static void shimmerTracesASine() {
+        double frequency = Math.PI; // One wave every 2 seconds
+        for (double seconds = 0; seconds < 4; seconds += 0.25) {
+            double expected = (Math.sin(seconds * frequency) + 1) / 2;
+            assert Math.abs(Spacerace.shimmerBrightness(seconds, frequency, 0) - expected) < 1e-12 : seconds;
+        }
+        assert Math.abs(Spacerace.shimmerBrightness(0.5, frequency, 0) - 1) < 1e-12 : "peaks a quarter wave in";
+        assert Math.abs(Spacerace.shimmerBrightness(1.5, frequency, 0)) < 1e-12 : "goes dark at three quarters";
+        assert Math.abs(Spacerace.shimmerBrightness(2.5, frequency, 0) - 1) < 1e-12 : "and repeats every 2 seconds";
+        char[] fade = {'*', '+', '.', ' '};
+        assert Spacerace.brightnessGlyph(fade, 1) == '*' && Spacerace.brightnessGlyph(fade, 0) == ' ';
+    }
+
+    static void keyPressesAreLogged() throws Exception {
+        Path log = Files.createTempFile("spacerace", ".log");
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        spacerace.startEventLog(log.toString());
+        captureOut(() -> {
+            spacerace.handleKey("p");
+            spacerace.handleKey("z");
+        });
+        List<String> lines = Files.readAllLines(log);
+        assert lines.equals(List.of("2024-01-10T12:00:00Z\tkey p\tpause", "2024-01-10T12:00:00Z\tkey z\tnone")) : lines;
+    }
+
+    static void writesAfterShutdownAreDropped() throws Exception {
+        Path log = Files.createTempFile("spacerace", ".log");
+        Path record = Files.createTempFile("spacerace", ".rec");
+        Spacerace spacerace = loadFixture("space010.txt", 1);
+        spacerace.startEventLog(log.toString());
+        spacerace.startRecording(record.toString());
+        Thread keys = new Thread(() -> {
+            for (int i = 0; i < 2000; i++) {
+                spacerace.handleKey("z");
+            }
+        });
+        keys.start();
+        spacerace.shutdown();
+        keys.join();
+        long logged = Files.size(log);
+        captureOut(() -> spacerace.handleKey("z"));
+        spacerace.recordFrame(List.of("late"), 0);
+        assert Files.size(log) == logged : "key press logged after shutdown";
+        assert Files.size(record) == 0 : "frame recorded after shutdown";
+    }
+
+    static void viewpointPicksTheArtFromItsOwnOrbit() throws IOException {
+        List<Spacerace.Body> bodies = Spacerace.Body.fromConfig(Files.readAllLines(Path.of("bodies.txt")));
+        Spacerace.Body mars = Spacerace.findBody(bodies, "mars");
+        LocalDate date = INSTANT.toLocalDate();
+        Spacerace spacerace = new Spacerace();
+        int earthKey = Spacerace.bucketKey(spacerace.artMap(), spacerace.artPosition(date));
+        spacerace.setViewpoint(mars);
+        int marsKey = Spacerace.bucketKey(spacerace.artMap(), spacerace.artPosition(date));
+        assert marsKey == Spacerace.bucketKey(spacerace.artMap(), mars.position(date)) : marsKey;
+        assert marsKey != earthKey : earthKey + " from both";
+    }
+
+    static void fadeInMakesTheConfiguredSteps() {
+        String[] art = {"*****", "*****"};
+        List<String[]> frames = Spacerace.fadeInFrames(art, 4);
+        assert frames.size() == 4 : frames.size();
+        long shown = -1;
+        for (String[] frame : frames) {
+            long stars = String.join("", frame).chars().filter(c -> c == '*').count();
+            assert stars >= shown && stars < 10 : stars + " after " + shown;
+            shown = stars;
+        }
+        assert shown > 0 : "the last step shows some of the art";
+        assert Spacerace.fadeInFrames(art, 0).isEmpty() : "--fade-in 0 pops in";
+    }
+
+    static void raggedArtReportsItsWidestLine() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace");
+        Files.write(dir.resolve("space010.txt"), List.of("**", "*****", "星星星", "é"), StandardCharsets.UTF_8);
+        Spacerace spacerace = new Spacerace();
+        spacerace.setArtRoot(dir.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.asciiArt(dir.resolve("space010.txt").toString());
+        int[] size = spacerace.artDimensions();
+        assert size[0] == 6 && size[1] == 4 : size[0] + "x" + size[1];
+        assert Spacerace.displayWidth("é") == 1 : "combining marks take no column";
+    }
+
+    static void averageFpsOverWindow() {
+        assert Spacerace.averageFps(List.of()) == 0;
+        assert Spacerace.averageFps(List.of(20_000_000L, 20_000_000L, 20_000_000L)) == 50.0;
+        assert Spacerace.averageFps(List.of(10_000_000L, 30_000_000L)) == 50.0 : "averaged, not the last frame";
+    }
+
+    static void rampColorsStarsByBrightness() {
+        assert Arrays.equals(Spacerace.rampColor(1.0), new int[]{255, 240, 200}) : Arrays.toString(Spacerace.rampColor(1.0));
+        assert Arrays.equals(Spacerace.rampColor(0.2), new int[]{85, 106, 179}) : Arrays.toString(Spacerace.rampColor(0.2));
+        assert Arrays.equals(Spacerace.Palette.RAMP.starColor('*', 0), Spacerace.rampColor(1.0)) : "a full star is the warm end";
+        assert Arrays.equals(Spacerace.Palette.RAMP.starColor('.', 0), Spacerace.rampColor(0.2)) : "a faint star is cool";
+    }
+
+    static void stateAfterALoadIsConsistent() throws IOException {
+        Spacerace spacerace = configured("--art-root", FIXTURES.toString());
+        spacerace.setInstant(INSTANT);
+        spacerace.setSeed(1);
+        spacerace.loadArtForDate(INSTANT.toLocalDate());
+        spacerace.nextFrame();
+        Spacerace.RenderState state = spacerace.state();
+        assert state.angle == Spacerace.calculateEarthPosition(INSTANT.toLocalDate()) : state.angle;
+        assert state.bucketKey == 0 : state.bucketKey;
+        assert state.artPath.equals(spacerace.resolveArtPathForDate(INSTANT.toLocalDate())) : state.artPath;
+        assert state.season.equals("Winter") : state.season;
+        assert state.frame == 1 : state.frame;
+        assert state.moonPhase >= 0 && state.moonPhase < 1 : state.moonPhase;
+        assert state.artHeight > 0 && state.artWidth == spacerace.artDimensions()[0] && state.artHeight == spacerace.artDimensions()[1];
+    }
+
+    static void orbitDialMarksTheOctant() {
+        int[][] directions = {{-1, 0}, {-1, 1}, {0, 1}, {1, 1}, {1, 0}, {1, -1}, {0, -1}, {-1, -1}};
+        for (int octant = 0; octant < 8; octant++) {
+            String[] dial = Spacerace.orbitDial(octant * 45);
+            int[] earth = null;
+            int[] sun = null;
+            for (int row = 0; row < dial.length; row++) {
+                if (dial[row].indexOf('●') >= 0) {
+                    earth = new int[]{row, dial[row].indexOf('●')};
+                }
+                if (dial[row].indexOf('☉') >= 0) {
+                    sun = new int[]{row, dial[row].indexOf('☉')};
+                }
+            }
+            int[] seen = {Integer.signum(earth[0] - sun[0]), Integer.signum(earth[1] - sun[1])};
+            assert Arrays.equals(seen, directions[octant]) : (octant * 45) + " at " + Arrays.toString(seen);
+        }
+        assert Arrays.equals(Spacerace.dialCell(90), new int[]{3, 12}) : Arrays.toString(Spacerace.dialCell(90));
+    }
+
+    static void cycleSkipsMissingArt() throws IOException {
+        Path dir = Files.createTempDirectory("spacerace");
+        Files.write(dir.resolve("space010.txt"), List.of("  *  ", " *** "));
+        Spacerace spacerace = configured("--art-root", dir.toString(), "--cycle", "1", "--interval", "500");
+        List<Integer> playable = spacerace.playableBuckets();
+        assert playable.size() == 1 && spacerace.artMap().get(playable.get(0)).equals("space010.txt") : playable;
+        for (long frame = 0; frame < spacerace.artBuckets().size() * 2L; frame++) {
+            assert spacerace.cycleBucket(frame) == playable.get(0) : "frame " + frame + " left the one file there";
+        }
+        Spacerace empty = configured("--art-root", Files.createTempDirectory("spacerace").toString(), "--cycle", "1");
+        assert empty.playableBuckets().equals(empty.artBuckets()) : "nothing playable keeps every bucket";
+    }
+
+    static void windShiftsThePhaseDownwind() {
+        double frequency = 2;
+        double shift = Spacerace.windPhase(0, 0, 1, 0) - Spacerace.windPhase(0, 1, 1, 0);
+        assert Math.abs(shift - 0.3) < 1e-9 : shift;
+        for (double seconds = 0; seconds < 3; seconds += 0.25) {
+            double upwind = Spacerace.shimmerBrightness(seconds, frequency, Spacerace.windPhase(4, 7, 1, 0));
+            double downwind = Spacerace.shimmerBrightness(seconds + shift / frequency, frequency,
+                    Spacerace.windPhase(4, 8, 1, 0));
+            assert Math.abs(upwind - downwind) < 1e-9 : "the next column lags at " + seconds + "s";
+        }
+        assert Spacerace.windPhase(4, 7, 0, 2) == Spacerace.windPhase(4, 8, 0, 2) : "no shift across the wind";
+        assert Spacerace.windPhase(4, 7, 0, 0) == 0 : "no wind, no shift";
+    }
+
+    static void mapGapsOfTheDefaultMap() throws Exception {
+        Spacerace spacerace = new Spacerace();
+        assert Spacerace.mapGaps(spacerace.artMap(), 45).isEmpty() : "the default limit is quiet on the built in map";
+        assert configured().configToml().contains("max_gap = 45") : configured().configToml();
+        List<String> gaps = Spacerace.mapGaps(spacerace.artMap(), 25);
+        assert gaps.equals(List.of(
+                "No art between 60° and 90°, space6070.txt covers 30 degrees",
+                "No art between 90° and 120°, space90110.txt covers 30 degrees",
+                "No art between 240° and 270°, space240260.txt covers 30 degrees",
+                "No art between 270° and 300°, space270290.txt covers 30 degrees")) : gaps;
+    }
+
+    static void mapGapsFindsWideStretches() {
+        NavigableMap<Integer, String> map = new TreeMap<>();
+        map.put(0, "a.txt");
+        map.put(90, "b.txt");
+        map.put(180, "c.txt");
+        assert Spacerace.mapGaps(map, 180).isEmpty();
+        List<String> gaps = Spacerace.mapGaps(map, 100);
+        assert gaps.equals(List.of("No art between 180° and 0°, c.txt covers 180 degrees")) : gaps;
+        assert Spacerace.mapGaps(map, 80).size() == 3;
+    }
+
+    static void blackImageIsTheDarkestRampCharacter() {
+        BufferedImage black = new BufferedImage(40, 20, BufferedImage.TYPE_INT_RGB);
+        for (String line : Spacerace.imageToAscii(black, 12, 5, "_abc")) {
+            assert line.equals("_".repeat(12)) : line;
+        }
+        String[] composed = Spacerace.compositeOver(new String[]{" * "}, new String[]{"___"});
+        assert composed[0].equals("_*_") : "stars stay on top, got " + composed[0];
+    }
+
+    private static List<List<String>> frames(Spacerace spacerace, int count) {
+        List<List<String>> frames = new ArrayList<>();
+        for (int i = 0; i < count; i++) {
+            frames.add(spacerace.nextFrame());
+        }
+        return frames;
+    }
+}